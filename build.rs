@@ -1,5 +1,11 @@
 extern crate embed_resource;
-use std::env;
+use std::{env, fs};
+
+// put together from these by build/credits.sh, shown on the credits screen
+const CREDITS_SOURCES: [&str; 2] = ["credits/CREDITS.md", "CREDIT.md"];
+const CREDITS_LICENSES: &str = "credits/licenses";
+// loaded at runtime, so it lives with the other assets and ships with the web build
+const CREDITS_ASSET: &str = "assets/credits/game.credits.md";
 
 fn main() {
     let target = env::var("TARGET").unwrap();
@@ -7,4 +13,64 @@ fn main() {
         // on windows we will set our game icon as icon for the executable
         embed_resource::compile("build/windows/icon.rc");
     }
+
+    check_credits();
+}
+
+// fails the build if any of the credits are missing or the asset is stale, the game shouldn't
+// ship without them, only checks so the build never writes outside target/
+fn check_credits() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed={}", CREDITS_LICENSES);
+    println!("cargo:rerun-if-changed={}", CREDITS_ASSET);
+
+    // same as build/credits.sh puts out
+    let mut text = String::from(
+        "<!-- generated by build/credits.sh from CREDIT.md and credits/, edit those instead -->\n",
+    );
+    for source in CREDITS_SOURCES {
+        println!("cargo:rerun-if-changed={}", source);
+        let contents = fs::read_to_string(source)
+            .unwrap_or_else(|e| panic!("can't read {} for the credits screen: {}", source, e));
+        text.push('\n');
+        text.push_str(contents.trim_end_matches('\n'));
+        text.push('\n');
+    }
+
+    let mut licenses = fs::read_dir(CREDITS_LICENSES)
+        .unwrap_or_else(|e| panic!("can't read {}: {}", CREDITS_LICENSES, e))
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().map_or(false, |ext| ext == "md"))
+        .collect::<Vec<_>>();
+    licenses.sort();
+    if !licenses.is_empty() {
+        text.push_str("\n# Licenses\n");
+    }
+    for path in licenses {
+        println!("cargo:rerun-if-changed={}", path.display());
+        let name = path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .replace('_', " ");
+        let contents = fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("can't read {}: {}", path.display(), e));
+        text.push_str(&format!("\n## {}\n\n", name));
+        text.push_str(contents.trim_end_matches('\n'));
+        text.push('\n');
+    }
+
+    let asset = fs::read_to_string(CREDITS_ASSET).unwrap_or_else(|e| {
+        panic!(
+            "can't read {}, run build/credits.sh to generate it: {}",
+            CREDITS_ASSET, e
+        )
+    });
+    // checkouts with windows line endings
+    if asset.replace('\r', "") != text.replace('\r', "") {
+        panic!(
+            "{} is out of date with CREDIT.md and credits/, run build/credits.sh",
+            CREDITS_ASSET
+        );
+    }
 }
@@ -0,0 +1,25 @@
+#!/bin/sh
+# puts assets/credits/game.credits.md together from CREDIT.md and credits/
+# build.rs fails the build until this is run again after either changes
+set -e
+export LC_ALL=C
+cd "$(dirname "$0")/.."
+
+out=assets/credits/game.credits.md
+mkdir -p "$(dirname "$out")"
+{
+    echo "<!-- generated by build/credits.sh from CREDIT.md and credits/, edit those instead -->"
+    for source in credits/CREDITS.md CREDIT.md; do
+        echo
+        printf '%s\n' "$(cat "$source")"
+    done
+
+    set -- credits/licenses/*.md
+    if [ -e "$1" ]; then
+        printf '\n# Licenses\n'
+        for license in "$@"; do
+            printf '\n## %s\n\n' "$(basename "$license" .md | tr _ ' ')"
+            printf '%s\n' "$(cat "$license")"
+        done
+    fi
+} > "$out"
@@ -0,0 +1,622 @@
+use std::collections::HashMap;
+
+use bevy::{
+    input::{
+        mouse::{MouseMotion, MouseScrollUnit, MouseWheel},
+        InputSystem,
+    },
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+
+pub struct ActionPlugin;
+
+impl Plugin for ActionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActionState>()
+            .init_resource::<Rebinding>()
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                rebind.after(InputSystem).before(update_action_state),
+            )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                update_action_state.label(ActionSystem).after(InputSystem),
+            );
+    }
+}
+
+#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ActionSystem;
+
+// Everything the player can do, systems should read these instead of raw input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    MoveForward,
+    MoveBack,
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    Run,
+    Jump,
+    EditorLook,
+    EditorOrbit,
+    EditorPan,
+    EditorFocus,
+    EditorSelect,
+    FireBlue,
+    FireYellow,
+    Grab,
+    Interact,
+    Pause,
+    UnlockCursor,
+    SkipDialog,
+    SkipCredits,
+    ToggleDebug,
+    TogglePhysicsDebug,
+    ToggleCamera,
+    MenuUp,
+    MenuDown,
+    MenuConfirm,
+}
+
+impl Action {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        [
+            Action::MoveForward,
+            Action::MoveBack,
+            Action::MoveLeft,
+            Action::MoveRight,
+            Action::MoveUp,
+            Action::MoveDown,
+            Action::Run,
+            Action::Jump,
+            Action::EditorLook,
+            Action::EditorOrbit,
+            Action::EditorPan,
+            Action::EditorFocus,
+            Action::EditorSelect,
+            Action::FireBlue,
+            Action::FireYellow,
+            Action::Grab,
+            Action::Interact,
+            Action::Pause,
+            Action::UnlockCursor,
+            Action::SkipDialog,
+            Action::SkipCredits,
+            Action::ToggleDebug,
+            Action::TogglePhysicsDebug,
+            Action::ToggleCamera,
+            Action::MenuUp,
+            Action::MenuDown,
+            Action::MenuConfirm,
+        ]
+        .into_iter()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    // hold the first key, then press the second
+    Chord(KeyCode, KeyCode),
+    Gamepad(GamepadButtonType),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Device {
+    Keyboard,
+    Mouse,
+    Gamepad,
+}
+
+impl Binding {
+    pub fn device(&self) -> Device {
+        match self {
+            Binding::Key(_) | Binding::Chord(..) => Device::Keyboard,
+            Binding::Mouse(_) => Device::Mouse,
+            Binding::Gamepad(_) => Device::Gamepad,
+        }
+    }
+
+    // short name for prompts
+    pub fn label(&self) -> String {
+        match *self {
+            Binding::Key(key) => format!("{:?}", key),
+            Binding::Chord(modifier, key) => format!("{:?}+{:?}", modifier, key),
+            Binding::Mouse(MouseButton::Left) => "LMB".to_string(),
+            Binding::Mouse(MouseButton::Right) => "RMB".to_string(),
+            Binding::Mouse(MouseButton::Middle) => "MMB".to_string(),
+            Binding::Mouse(MouseButton::Other(n)) => format!("Mouse{}", n),
+            Binding::Gamepad(button) => format!("{:?}", button),
+        }
+    }
+
+    // 0-1, analog for triggers, everything else is on or off
+    fn value(&self, input: &RawInput) -> f32 {
+        match *self {
+            Binding::Key(key) => on_off(input.keys.pressed(key)),
+            Binding::Mouse(button) => on_off(input.mouse.pressed(button)),
+            Binding::Chord(modifier, key) => {
+                on_off(input.keys.pressed(modifier) && input.keys.pressed(key))
+            }
+            Binding::Gamepad(button_type) => input
+                .gamepads
+                .iter()
+                .map(|&gamepad| {
+                    let button = GamepadButton::new(gamepad, button_type);
+                    match input.gamepad_axes.get(button) {
+                        Some(value) if is_trigger(button_type) => {
+                            dead_zone(value, input.settings.trigger_dead_zone)
+                        }
+                        _ => on_off(input.gamepad_buttons.pressed(button)),
+                    }
+                })
+                .fold(0.0, f32::max),
+        }
+    }
+
+    fn just_pressed(&self, input: &RawInput) -> bool {
+        match *self {
+            Binding::Key(key) => input.keys.just_pressed(key),
+            Binding::Mouse(button) => input.mouse.just_pressed(button),
+            Binding::Chord(modifier, key) => {
+                input.keys.pressed(modifier) && input.keys.just_pressed(key)
+            }
+            Binding::Gamepad(button_type) => input.gamepads.iter().any(|&gamepad| {
+                input
+                    .gamepad_buttons
+                    .just_pressed(GamepadButton::new(gamepad, button_type))
+            }),
+        }
+    }
+}
+
+// everything a binding might need to read
+struct RawInput<'a> {
+    keys: &'a Input<KeyCode>,
+    mouse: &'a Input<MouseButton>,
+    gamepads: &'a Gamepads,
+    gamepad_buttons: &'a Input<GamepadButton>,
+    gamepad_axes: &'a Axis<GamepadButton>,
+    settings: &'a GamepadSettings,
+}
+
+fn on_off(pressed: bool) -> f32 {
+    if pressed {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+fn is_trigger(button_type: GamepadButtonType) -> bool {
+    matches!(
+        button_type,
+        GamepadButtonType::LeftTrigger2 | GamepadButtonType::RightTrigger2
+    )
+}
+
+// rescales so the value starts at 0 right after the dead zone
+fn dead_zone(value: f32, dead_zone: f32) -> f32 {
+    let dead_zone = dead_zone.clamp(0.0, 0.99);
+    if value.abs() <= dead_zone {
+        0.0
+    } else {
+        value.signum() * (value.abs() - dead_zone) / (1.0 - dead_zone)
+    }
+}
+
+// radial dead zone for sticks, then a power curve so small movements stay precise
+fn stick(value: Vec2, dead_zone: f32, curve: f32) -> Vec2 {
+    let length = value.length().min(1.0);
+    let scaled = self::dead_zone(length, dead_zone);
+    if scaled <= 0.0 {
+        Vec2::ZERO
+    } else {
+        value.normalize() * scaled.powf(curve.max(0.1))
+    }
+}
+
+// Stick and trigger tuning, saved with the rest of the settings
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct GamepadSettings {
+    pub move_dead_zone: f32,
+    pub look_dead_zone: f32,
+    pub trigger_dead_zone: f32,
+    // 1 is linear, higher gives finer control near the center
+    pub move_curve: f32,
+    pub look_curve: f32,
+    // full stick look speed, in the same units as mouse motion per frame
+    pub look_speed: f32,
+    pub invert_y: bool,
+}
+
+impl Default for GamepadSettings {
+    fn default() -> Self {
+        Self {
+            move_dead_zone: 0.15,
+            look_dead_zone: 0.1,
+            trigger_dead_zone: 0.05,
+            move_curve: 1.0,
+            look_curve: 2.0,
+            look_speed: 30.0,
+            invert_y: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct MouseSettings {
+    // radians per pixel of motion, per second
+    pub sensitivity: f32,
+    pub invert_y: bool,
+}
+
+impl Default for MouseSettings {
+    fn default() -> Self {
+        Self {
+            sensitivity: 0.1,
+            invert_y: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct InputMap {
+    pub bindings: HashMap<Action, Vec<Binding>>,
+    pub mouse: MouseSettings,
+    pub gamepad: GamepadSettings,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        use GamepadButtonType as Pad;
+
+        let mut bindings = HashMap::new();
+        bindings.insert(Action::MoveForward, vec![Binding::Key(KeyCode::W)]);
+        bindings.insert(Action::MoveBack, vec![Binding::Key(KeyCode::S)]);
+        bindings.insert(Action::MoveLeft, vec![Binding::Key(KeyCode::A)]);
+        bindings.insert(Action::MoveRight, vec![Binding::Key(KeyCode::D)]);
+        bindings.insert(
+            Action::MoveUp,
+            vec![
+                Binding::Key(KeyCode::E),
+                Binding::Gamepad(Pad::RightTrigger),
+            ],
+        );
+        bindings.insert(
+            Action::MoveDown,
+            vec![Binding::Key(KeyCode::Q), Binding::Gamepad(Pad::LeftTrigger)],
+        );
+        bindings.insert(
+            Action::Run,
+            vec![
+                Binding::Key(KeyCode::LShift),
+                Binding::Gamepad(Pad::LeftThumb),
+            ],
+        );
+        bindings.insert(
+            Action::Jump,
+            vec![Binding::Key(KeyCode::Space), Binding::Gamepad(Pad::South)],
+        );
+        bindings.insert(Action::EditorLook, vec![Binding::Mouse(MouseButton::Right)]);
+        // hold while looking to orbit the pivot instead
+        bindings.insert(Action::EditorOrbit, vec![Binding::Key(KeyCode::LAlt)]);
+        bindings.insert(Action::EditorPan, vec![Binding::Mouse(MouseButton::Middle)]);
+        bindings.insert(Action::EditorFocus, vec![Binding::Key(KeyCode::F)]);
+        bindings.insert(
+            Action::EditorSelect,
+            vec![Binding::Mouse(MouseButton::Left)],
+        );
+        bindings.insert(
+            Action::FireBlue,
+            vec![
+                Binding::Mouse(MouseButton::Left),
+                Binding::Gamepad(Pad::LeftTrigger2),
+            ],
+        );
+        bindings.insert(
+            Action::FireYellow,
+            vec![
+                Binding::Mouse(MouseButton::Right),
+                Binding::Gamepad(Pad::RightTrigger2),
+            ],
+        );
+        bindings.insert(
+            Action::Grab,
+            vec![
+                Binding::Mouse(MouseButton::Middle),
+                Binding::Gamepad(Pad::North),
+            ],
+        );
+        bindings.insert(
+            Action::Interact,
+            // not the mouse, left click fires the blaster
+            vec![Binding::Key(KeyCode::E), Binding::Gamepad(Pad::West)],
+        );
+        bindings.insert(
+            Action::Pause,
+            vec![Binding::Key(KeyCode::Escape), Binding::Gamepad(Pad::Start)],
+        );
+        bindings.insert(Action::UnlockCursor, vec![Binding::Key(KeyCode::LAlt)]);
+        bindings.insert(
+            Action::SkipDialog,
+            vec![
+                Binding::Chord(KeyCode::LAlt, KeyCode::S),
+                Binding::Gamepad(Pad::Select),
+            ],
+        );
+        bindings.insert(
+            Action::SkipCredits,
+            vec![
+                Binding::Mouse(MouseButton::Left),
+                Binding::Gamepad(Pad::South),
+            ],
+        );
+        bindings.insert(Action::ToggleDebug, vec![Binding::Key(KeyCode::F1)]);
+        bindings.insert(Action::TogglePhysicsDebug, vec![Binding::Key(KeyCode::F2)]);
+        bindings.insert(Action::ToggleCamera, vec![Binding::Key(KeyCode::F3)]);
+        bindings.insert(
+            Action::MenuUp,
+            vec![Binding::Key(KeyCode::Up), Binding::Gamepad(Pad::DPadUp)],
+        );
+        bindings.insert(
+            Action::MenuDown,
+            vec![Binding::Key(KeyCode::Down), Binding::Gamepad(Pad::DPadDown)],
+        );
+        bindings.insert(
+            Action::MenuConfirm,
+            vec![Binding::Key(KeyCode::Return), Binding::Gamepad(Pad::South)],
+        );
+        Self {
+            bindings,
+            mouse: MouseSettings::default(),
+            gamepad: GamepadSettings::default(),
+        }
+    }
+}
+
+impl InputMap {
+    pub fn get(&self, action: Action) -> &[Binding] {
+        self.bindings
+            .get(&action)
+            .map(|b| b.as_slice())
+            .unwrap_or_default()
+    }
+
+    // actions added since the settings were saved get their default bindings
+    pub fn add_missing_defaults(&mut self) {
+        for (action, bindings) in InputMap::default().bindings {
+            self.bindings.entry(action).or_insert(bindings);
+        }
+    }
+
+    // replaces the bindings for the same device, so rebinding a key keeps the mouse and pad bindings
+    pub fn rebind(&mut self, action: Action, binding: Binding) {
+        let bindings = self.bindings.entry(action).or_default();
+        bindings.retain(|b| b.device() != binding.device());
+        bindings.push(binding);
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+struct ButtonState {
+    value: f32,
+    pressed: bool,
+    just_pressed: bool,
+    just_released: bool,
+}
+
+// Resolved actions for this frame
+#[derive(Default)]
+pub struct ActionState {
+    buttons: HashMap<Action, ButtonState>,
+    movement: Vec2,
+    look: Vec2,
+    scroll: f32,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.buttons.get(&action).map_or(false, |b| b.pressed)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.buttons.get(&action).map_or(false, |b| b.just_pressed)
+    }
+
+    pub fn just_released(&self, action: Action) -> bool {
+        self.buttons.get(&action).map_or(false, |b| b.just_released)
+    }
+
+    // 0-1, how far a trigger is pulled, 1 for digital inputs
+    pub fn value(&self, action: Action) -> f32 {
+        self.buttons.get(&action).map_or(0.0, |b| b.value)
+    }
+
+    pub fn any_pressed(&self, actions: impl IntoIterator<Item = Action>) -> bool {
+        actions.into_iter().any(|a| self.pressed(a))
+    }
+
+    // -1 to 1 between two opposing actions
+    pub fn axis(&self, negative: Action, positive: Action) -> f32 {
+        let mut value = 0.0;
+        if self.pressed(positive) {
+            value += 1.0;
+        }
+        if self.pressed(negative) {
+            value -= 1.0;
+        }
+        value
+    }
+
+    // x is right, y is forward, length is at most 1, combines keys and the left stick
+    pub fn movement(&self) -> Vec2 {
+        self.movement
+    }
+
+    // look delta for this frame, mouse plus right stick
+    pub fn look(&self) -> Vec2 {
+        self.look
+    }
+
+    // scroll wheel notches this frame, positive is away from the player
+    pub fn scroll(&self) -> f32 {
+        self.scroll
+    }
+}
+
+// roughly how many pixels a touchpad scrolls per wheel notch
+const PIXELS_PER_LINE: f32 = 20.0;
+
+// a trigger only counts as pressed once it's pulled this far
+const PRESS_THRESHOLD: f32 = 0.1;
+
+fn update_action_state(
+    keys: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    mut mouse_motion: EventReader<MouseMotion>,
+    mut mouse_wheel: EventReader<MouseWheel>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_buttons_axis: Res<Axis<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    settings: Res<Settings>,
+    mut state: ResMut<ActionState>,
+) {
+    let pad = &settings.input.gamepad;
+    let input = RawInput {
+        keys: &keys,
+        mouse: &mouse,
+        gamepads: &gamepads,
+        gamepad_buttons: &gamepad_buttons,
+        gamepad_axes: &gamepad_buttons_axis,
+        settings: pad,
+    };
+
+    for action in Action::iter() {
+        let bindings = settings.input.get(action);
+        let value = bindings.iter().map(|b| b.value(&input)).fold(0.0, f32::max);
+        let pressed = value > PRESS_THRESHOLD;
+        let just_pressed = bindings.iter().any(|b| b.just_pressed(&input));
+        // triggers have their own threshold, so catch the edge ourselves
+        let analog = bindings
+            .iter()
+            .any(|b| matches!(b, Binding::Gamepad(button_type) if is_trigger(*button_type)));
+
+        let button = state.buttons.entry(action).or_default();
+        button.just_released = button.pressed && !pressed;
+        button.just_pressed = just_pressed || (analog && pressed && !button.pressed);
+        button.pressed = pressed;
+        button.value = value;
+    }
+
+    // sticks, first pad that is being used wins
+    let mut left_stick = Vec2::ZERO;
+    let mut right_stick = Vec2::ZERO;
+    for &gamepad in gamepads.iter() {
+        let axis = |axis_type| {
+            gamepad_axes
+                .get(GamepadAxis::new(gamepad, axis_type))
+                .unwrap_or_default()
+        };
+        let left = stick(
+            Vec2::new(
+                axis(GamepadAxisType::LeftStickX),
+                axis(GamepadAxisType::LeftStickY),
+            ),
+            pad.move_dead_zone,
+            pad.move_curve,
+        );
+        let right = stick(
+            Vec2::new(
+                axis(GamepadAxisType::RightStickX),
+                axis(GamepadAxisType::RightStickY),
+            ),
+            pad.look_dead_zone,
+            pad.look_curve,
+        );
+        if left_stick == Vec2::ZERO {
+            left_stick = left;
+        }
+        if right_stick == Vec2::ZERO {
+            right_stick = right;
+        }
+    }
+
+    let keys_movement = Vec2::new(
+        state.axis(Action::MoveLeft, Action::MoveRight),
+        state.axis(Action::MoveBack, Action::MoveForward),
+    );
+    state.movement = (keys_movement + left_stick).clamp_length_max(1.0);
+
+    // stick up looks up, mouse motion is in screen space so y is flipped
+    let invert = if pad.invert_y { -1.0 } else { 1.0 };
+    state.look = Vec2::new(right_stick.x, -right_stick.y * invert) * pad.look_speed;
+    let mouse_invert = if settings.input.mouse.invert_y {
+        -1.0
+    } else {
+        1.0
+    };
+    for event in mouse_motion.iter() {
+        state.look += Vec2::new(event.delta.x, event.delta.y * mouse_invert);
+    }
+
+    state.scroll = 0.0;
+    for event in mouse_wheel.iter() {
+        state.scroll += match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / PIXELS_PER_LINE,
+        };
+    }
+}
+
+// Set to an action to bind it to the next key, mouse or gamepad button pressed, Escape cancels
+#[derive(Default)]
+pub struct Rebinding(pub Option<Action>);
+
+fn rebind(
+    mut keys: ResMut<Input<KeyCode>>,
+    mut mouse: ResMut<Input<MouseButton>>,
+    mut gamepad_buttons: ResMut<Input<GamepadButton>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut settings: ResMut<Settings>,
+) {
+    let action = match rebinding.0 {
+        Some(action) => action,
+        None => return,
+    };
+
+    let binding = if let Some(button) = gamepad_buttons.get_just_pressed().next() {
+        Binding::Gamepad(button.button_type)
+    } else if let Some(key) = keys.get_just_pressed().next().copied() {
+        if key == KeyCode::Escape {
+            keys.clear();
+            rebinding.0 = None;
+            return;
+        }
+        Binding::Key(key)
+    } else if let Some(button) = mouse.get_just_pressed().next().copied() {
+        Binding::Mouse(button)
+    } else {
+        return;
+    };
+
+    info!("bound {:?} to {:?}", action, binding);
+    settings.input.rebind(action, binding);
+    settings.save();
+    rebinding.0 = None;
+
+    // don't let the press that set the binding trigger anything
+    keys.clear();
+    mouse.clear();
+    gamepad_buttons.clear();
+}
@@ -2,6 +2,14 @@ use bevy::{gltf::Gltf, prelude::*};
 use bevy_asset_loader::prelude::*;
 use bevy_kira_audio::AudioSource;
 
+use crate::{
+    locale::{Language, StringTable},
+    music::MusicTrack,
+    prefabs::{BarkSet, DialogueScript},
+    states::Credits,
+    subtitles::CaptionSet,
+};
+
 pub const CLEAR: Color = Color::rgba(0.0, 0.0, 0.0, 0.0);
 
 pub struct ButtonColors {
@@ -22,6 +30,10 @@ impl Default for ButtonColors {
 pub struct FontAssets {
     #[asset(path = "fonts/FiraSans-Bold.ttf")]
     pub fira_sans: Handle<Font>,
+
+    // what everything is drawn with, the locale swaps in the language's own font when it has one
+    #[asset(path = "fonts/FiraSans-Bold.ttf")]
+    pub text: Handle<Font>,
 }
 
 impl FontAssets {
@@ -29,7 +41,7 @@ impl FontAssets {
         TextSection {
             value: text,
             style: TextStyle {
-                font: self.fira_sans.clone(),
+                font: self.text.clone(),
                 font_size: 30.0,
                 color,
             },
@@ -40,7 +52,7 @@ impl FontAssets {
         TextSection {
             value: text,
             style: TextStyle {
-                font: self.fira_sans.clone(),
+                font: self.text.clone(),
                 font_size: 90.0,
                 color,
             },
@@ -51,7 +63,7 @@ impl FontAssets {
         TextSection {
             value: text,
             style: TextStyle {
-                font: self.fira_sans.clone(),
+                font: self.text.clone(),
                 font_size: 16.0,
                 color,
             },
@@ -59,10 +71,69 @@ impl FontAssets {
     }
 }
 
+// loaded with the fonts, the loading screen is translated too
+#[derive(AssetCollection)]
+pub struct LocaleAssets {
+    #[asset(path = "locale/en.strings.ron")]
+    pub en: Handle<StringTable>,
+
+    #[asset(path = "locale/es.strings.ron")]
+    pub es: Handle<StringTable>,
+}
+
+impl LocaleAssets {
+    pub fn table(&self, language: Language) -> &Handle<StringTable> {
+        match language {
+            Language::English => &self.en,
+            Language::Spanish => &self.es,
+        }
+    }
+}
+
 #[derive(AssetCollection)]
 pub struct AudioAssets {
     #[asset(path = "audio/intro.ogg")]
     pub intro: Handle<AudioSource>,
+
+    #[asset(path = "music/reactor.music.ron")]
+    pub music: Handle<MusicTrack>,
+
+    // TODO: a generated drone standing in until a real hum is recorded
+    #[asset(path = "audio/reactor_hum.wav")]
+    pub reactor_hum: Handle<AudioSource>,
+}
+
+#[derive(AssetCollection)]
+pub struct DialogueAssets {
+    #[asset(path = "dialogue/intro.dialogue.ron")]
+    pub intro: Handle<DialogueScript>,
+
+    #[asset(path = "dialogue/nexus.barks.ron")]
+    pub barks: Handle<BarkSet>,
+}
+
+#[derive(AssetCollection)]
+pub struct CaptionAssets {
+    #[asset(path = "captions/en.captions.ron")]
+    pub en: Handle<CaptionSet>,
+
+    #[asset(path = "captions/es.captions.ron")]
+    pub es: Handle<CaptionSet>,
+}
+
+impl CaptionAssets {
+    pub fn captions(&self, language: Language) -> &Handle<CaptionSet> {
+        match language {
+            Language::English => &self.en,
+            Language::Spanish => &self.es,
+        }
+    }
+}
+
+#[derive(AssetCollection)]
+pub struct CreditAssets {
+    #[asset(path = "credits/game.credits.md")]
+    pub credits: Handle<Credits>,
 }
 
 #[derive(AssetCollection)]
@@ -86,66 +157,6 @@ pub struct TextureAssets {
     pub pattern_78: Handle<Image>,
 }
 
-#[derive(AssetCollection)]
-pub struct AIAudioAssets {
-    #[asset(path = "audio/ai/intro_1.ogg")]
-    pub intro_1: Handle<AudioSource>,
-
-    #[asset(path = "audio/ai/intro_2.ogg")]
-    pub intro_2: Handle<AudioSource>,
-
-    #[asset(path = "audio/ai/intro_3.ogg")]
-    pub intro_3: Handle<AudioSource>,
-
-    #[asset(path = "audio/ai/intro_4.ogg")]
-    pub intro_4: Handle<AudioSource>,
-
-    #[asset(path = "audio/ai/intro_5.ogg")]
-    pub intro_5: Handle<AudioSource>,
-
-    #[asset(path = "audio/ai/intro_6.ogg")]
-    pub intro_6: Handle<AudioSource>,
-
-    #[asset(path = "audio/ai/intro_7.ogg")]
-    pub intro_7: Handle<AudioSource>,
-
-    #[asset(path = "audio/ai/intro_8.ogg")]
-    pub intro_8: Handle<AudioSource>,
-
-    #[asset(path = "audio/ai/intro_9.ogg")]
-    pub intro_9: Handle<AudioSource>,
-
-    #[asset(path = "audio/ai/intro_10.ogg")]
-    pub intro_10: Handle<AudioSource>,
-
-    #[asset(path = "audio/ai/intro_check_2.ogg")]
-    pub intro_check_2: Handle<AudioSource>,
-
-    #[asset(path = "audio/ai/intro_check_3.ogg")]
-    pub intro_check_3: Handle<AudioSource>,
-
-    #[asset(path = "audio/ai/chris.ogg")]
-    pub annoyed0: Handle<AudioSource>,
-
-    #[asset(path = "audio/ai/hey_stop_that.ogg")]
-    pub annoyed1: Handle<AudioSource>,
-
-    #[asset(path = "audio/ai/whats_wrong_with_you.ogg")]
-    pub annoyed2: Handle<AudioSource>,
-
-    #[asset(path = "audio/ai/high_1.ogg")]
-    pub high_1: Handle<AudioSource>,
-
-    #[asset(path = "audio/ai/high_2.ogg")]
-    pub high_2: Handle<AudioSource>,
-
-    #[asset(path = "audio/ai/high_3.ogg")]
-    pub high_3: Handle<AudioSource>,
-
-    #[asset(path = "audio/ai/start.ogg")]
-    pub start: Handle<AudioSource>,
-}
-
 #[derive(AssetCollection)]
 pub struct SpaceKitAssets {
     #[asset(path = "spacekit/alien.glb#Scene0")]
@@ -3,17 +3,17 @@
 #![allow(clippy::type_complexity)]
 use std::f32::consts::FRAC_PI_2;
 
-use bevy::{
-    input::{mouse::MouseMotion, Input},
-    prelude::*,
-    window::{WindowFocused, Windows},
-};
+use bevy::{prelude::*, render::primitives::Aabb};
 use iyes_loopless::prelude::*;
 use sly_physics::prelude::*;
 
 use crate::{
+    actions::{Action, ActionState},
     assets::{TextureAssets, CLEAR},
-    prefabs::RoomConfig,
+    character::CharacterController,
+    cursor::{interaction_check, CursorEvent, Inspector},
+    cursor_lock::CursorLock,
+    settings::Settings,
     GameState, Keep,
 };
 
@@ -30,11 +30,23 @@ impl Plugin for CameraPlugin {
             .add_enter_system(GameState::Playing, set_camera_player)
             .add_exit_system(GameState::Playing, set_camera_static)
             .add_enter_system(CameraState::Player, setup_player_camera)
+            .add_exit_system(CameraState::Player, exit_player_camera)
             .add_system_to_stage(
                 CoreStage::PostUpdate,
                 update_player_camera.run_in_state(CameraState::Player),
             )
-            .add_exit_system(CameraState::Player, exit_player_camera)
+            .add_enter_system(CameraState::Editor, enter_editor_camera)
+            .add_exit_system(CameraState::Editor, exit_editor_camera)
+            .add_system(
+                select_in_editor
+                    .run_in_state(CameraState::Editor)
+                    .after(interaction_check),
+            )
+            .add_system(
+                focus_editor_camera
+                    .run_in_state(CameraState::Editor)
+                    .after(select_in_editor),
+            )
             .add_system_to_stage(
                 CoreStage::PostUpdate,
                 update_editor_camera.run_in_state(CameraState::Editor),
@@ -50,16 +62,7 @@ fn set_camera_static(mut commands: Commands) {
     commands.insert_resource(NextState(CameraState::Static));
 }
 
-fn setup_player_camera(
-    mut commands: Commands,
-    mut windows: ResMut<Windows>,
-    texture_assets: Res<TextureAssets>,
-) {
-    if let Some(window) = windows.get_primary_mut() {
-        window.set_cursor_lock_mode(true);
-        window.set_cursor_visibility(false);
-    }
-
+fn setup_player_camera(mut commands: Commands, texture_assets: Res<TextureAssets>) {
     commands
         .spawn_bundle(NodeBundle {
             style: Style {
@@ -77,6 +80,8 @@ fn setup_player_camera(
             color: CLEAR.into(),
             ..default()
         })
+        .insert(Crosshair)
+        .insert(Name::new("ui Crosshair"))
         .with_children(|parent| {
             parent.spawn_bundle(ImageBundle {
                 image: UiImage(texture_assets.crossair_black.clone()),
@@ -90,18 +95,22 @@ fn setup_player_camera(
         });
 }
 
-fn exit_player_camera(mut windows: ResMut<Windows>) {
-    if let Some(window) = windows.get_primary_mut() {
-        window.set_cursor_lock_mode(false);
-        window.set_cursor_visibility(true);
+fn exit_player_camera(mut commands: Commands, query: Query<Entity, With<Crosshair>>) {
+    for e in query.iter() {
+        commands.entity(e).despawn_recursive();
     }
 }
 
+#[derive(Component)]
+struct Crosshair;
+
 #[derive(Clone, Eq, PartialEq, Debug, Hash)]
 pub enum CameraState {
     Static,
     Editor,
     Player,
+    // camera is on a scripted path, see cinematic.rs
+    Cinematic,
 }
 
 #[derive(Component)]
@@ -109,10 +118,10 @@ pub struct CameraMain;
 
 fn toggle_camera(
     mut commands: Commands,
-    input: Res<Input<KeyCode>>,
+    actions: Res<ActionState>,
     camera_state: Res<CurrentState<CameraState>>,
 ) {
-    if input.just_pressed(KeyCode::F3) {
+    if actions.just_pressed(Action::ToggleCamera) {
         match camera_state.0 {
             CameraState::Static => {
                 commands.insert_resource(NextState(CameraState::Editor));
@@ -123,6 +132,8 @@ fn toggle_camera(
             CameraState::Player => {
                 commands.insert_resource(NextState(CameraState::Static));
             }
+            // let the path finish
+            CameraState::Cinematic => {}
         };
     }
 }
@@ -151,129 +162,224 @@ fn setup_camera(mut commands: Commands) {
             ..default()
         })
         .insert(CameraMain)
+        .insert(CharacterController::default())
         .insert(BvhCamera::new(256, 256))
         .insert(Keep);
 }
 
 pub struct CameraEditorConfig {
     pub sensitivity: f32,
-    pub key_forward: KeyCode,
-    pub key_back: KeyCode,
-    pub key_left: KeyCode,
-    pub key_right: KeyCode,
-    pub key_up: KeyCode,
-    pub key_down: KeyCode,
-    pub key_run: KeyCode,
-    pub mouse_look: MouseButton,
     pub walk_speed: f32,
     pub run_speed: f32,
     pub friction: f32,
     pub pitch: f32,
     pub yaw: f32,
     pub velocity: Vec3,
+    // scroll wheel multiplies walk and run speed by this
+    pub speed_scale: f32,
+    pub speed_step: f32,
+    pub min_speed_scale: f32,
+    pub max_speed_scale: f32,
+    // pan speed per pixel of mouse motion, scaled by orbit distance
+    pub pan_sensitivity: f32,
+    // pivot for orbiting sits this far in front of the camera
+    pub orbit_distance: f32,
+    // how long frame selected takes to get there
+    pub focus_time: f32,
+    // where the editor camera was last time we left it
+    pub pose: Option<Transform>,
+    flight: Option<Flight>,
 }
 
 impl Default for CameraEditorConfig {
     fn default() -> Self {
         CameraEditorConfig {
             sensitivity: 0.2,
-            key_forward: KeyCode::W,
-            key_back: KeyCode::S,
-            key_left: KeyCode::A,
-            key_right: KeyCode::D,
-            key_up: KeyCode::E,
-            key_down: KeyCode::Q,
-            key_run: KeyCode::LShift,
-            mouse_look: MouseButton::Right,
             walk_speed: 10.0,
             run_speed: 30.0,
             friction: 0.3,
             pitch: 0.0,
             yaw: 0.0,
             velocity: Vec3::ZERO,
+            speed_scale: 1.0,
+            speed_step: 1.2,
+            min_speed_scale: 0.1,
+            max_speed_scale: 10.0,
+            pan_sensitivity: 0.002,
+            orbit_distance: 5.0,
+            focus_time: 0.3,
+            pose: None,
+            flight: None,
+        }
+    }
+}
+
+// camera moving to frame the selected entity
+struct Flight {
+    start: Vec3,
+    end: Vec3,
+    timer: Timer,
+}
+
+fn enter_editor_camera(
+    mut query: Query<&mut Transform, With<CameraMain>>,
+    mut controller: ResMut<CameraEditorConfig>,
+) {
+    controller.velocity = Vec3::ZERO;
+    controller.flight = None;
+    if let Some(pose) = controller.pose {
+        for mut transform in query.iter_mut() {
+            *transform = pose;
+        }
+    }
+}
+
+fn exit_editor_camera(
+    query: Query<&Transform, With<CameraMain>>,
+    mut controller: ResMut<CameraEditorConfig>,
+) {
+    controller.pose = query.get_single().ok().copied();
+    controller.flight = None;
+}
+
+// anything the cursor ray hits can be picked in the editor, not just interactables
+fn select_in_editor(
+    mut cursor_events: EventReader<CursorEvent>,
+    actions: Res<ActionState>,
+    mut inspector: ResMut<Inspector>,
+) {
+    if let Some(event) = cursor_events.iter().last() {
+        if actions.just_pressed(Action::EditorSelect) {
+            inspector.select(event.entity);
         }
     }
 }
 
+// fly to the entity selected in the editor
+fn focus_editor_camera(
+    actions: Res<ActionState>,
+    inspector: Res<Inspector>,
+    camera_query: Query<&Transform, With<CameraMain>>,
+    target_query: Query<(&GlobalTransform, Option<&Aabb>)>,
+    mut controller: ResMut<CameraEditorConfig>,
+) {
+    if !actions.just_pressed(Action::EditorFocus) {
+        return;
+    }
+    let (target_trans, aabb) = match inspector.active().and_then(|e| target_query.get(e).ok()) {
+        Some(target) => target,
+        None => return,
+    };
+
+    // fit the bounds in view, things without a mesh get a default size
+    let (scale, _, _) = target_trans.to_scale_rotation_translation();
+    let (center, radius) = match aabb {
+        Some(aabb) => (
+            target_trans.mul_vec3(Vec3::from(aabb.center)),
+            (Vec3::from(aabb.half_extents) * scale).length(),
+        ),
+        None => (target_trans.translation(), 0.5),
+    };
+    let distance = (radius * 2.5).max(1.0);
+
+    let camera_trans = camera_query.single();
+    controller.velocity = Vec3::ZERO;
+    controller.orbit_distance = distance;
+    controller.flight = Some(Flight {
+        start: camera_trans.translation,
+        end: center - camera_trans.forward() * distance,
+        timer: Timer::from_seconds(controller.focus_time, false),
+    });
+}
+
 fn update_editor_camera(
     time: Res<Time>,
-    mut mouse_motion: EventReader<MouseMotion>,
-    key_input: Res<Input<KeyCode>>,
-    mouse_input: Res<Input<MouseButton>>,
+    actions: Res<ActionState>,
     mut query: Query<&mut Transform, With<CameraMain>>,
-    mut windows: ResMut<Windows>,
     mut controller: ResMut<CameraEditorConfig>,
+    cursor: Res<CursorLock>,
 ) {
     let dt = time.delta_seconds();
-    if let Some(window) = windows.get_primary_mut() {
-        for mut transform in query.iter_mut() {
-            // Handle key input
-            let mut axis_input = Vec3::ZERO;
-            if key_input.pressed(controller.key_forward) {
-                axis_input.z += 1.0;
-            }
-            if key_input.pressed(controller.key_back) {
-                axis_input.z -= 1.0;
-            }
-            if key_input.pressed(controller.key_right) {
-                axis_input.x += 1.0;
-            }
-            if key_input.pressed(controller.key_left) {
-                axis_input.x -= 1.0;
-            }
-            if key_input.pressed(controller.key_up) {
-                axis_input.y += 1.0;
-            }
-            if key_input.pressed(controller.key_down) {
-                axis_input.y -= 1.0;
+
+    // scroll changes speed
+    if actions.scroll() != 0.0 {
+        let scale = controller.speed_scale * controller.speed_step.powf(actions.scroll());
+        controller.speed_scale =
+            scale.clamp(controller.min_speed_scale, controller.max_speed_scale);
+    }
+
+    for mut transform in query.iter_mut() {
+        // frame selected, ignore everything else till we get there
+        if let Some(flight) = &mut controller.flight {
+            flight.timer.tick(time.delta());
+            let t = flight.timer.percent();
+            let t = t * t * (3.0 - 2.0 * t);
+            transform.translation = flight.start.lerp(flight.end, t);
+            if flight.timer.finished() {
+                controller.flight = None;
             }
+            continue;
+        }
 
-            // Apply movement update
-            if axis_input != Vec3::ZERO {
-                let max_speed = if key_input.pressed(controller.key_run) {
-                    controller.run_speed
-                } else {
-                    controller.walk_speed
-                };
-                controller.velocity = axis_input.normalize() * max_speed;
+        // Handle key input
+        let movement = actions.movement();
+        let axis_input = Vec3::new(
+            movement.x,
+            actions.axis(Action::MoveDown, Action::MoveUp),
+            movement.y,
+        );
+
+        // Apply movement update
+        if axis_input != Vec3::ZERO {
+            let max_speed = if actions.pressed(Action::Run) {
+                controller.run_speed
             } else {
-                let friction = controller.friction.clamp(0.0, 1.0);
-                controller.velocity *= 1.0 - friction;
-                if controller.velocity.length_squared() < 1e-6 {
-                    controller.velocity = Vec3::ZERO;
-                }
-            }
-            let forward = transform.forward();
-            let right = transform.right();
-            transform.translation += controller.velocity.x * dt * right
-                + controller.velocity.y * dt * Vec3::Y
-                + controller.velocity.z * dt * forward;
-
-            // Handle mouse look on mouse button
-            let mut mouse_delta = Vec2::ZERO;
-            if mouse_input.pressed(controller.mouse_look) {
-                #[cfg(not(target = "wasm32"))]
-                window.set_cursor_lock_mode(true);
-                window.set_cursor_visibility(false);
-            }
-            if mouse_input.just_released(controller.mouse_look) {
-                #[cfg(not(target = "wasm32"))]
-                window.set_cursor_lock_mode(false);
-                window.set_cursor_visibility(true);
-            }
-            if mouse_input.pressed(controller.mouse_look) {
-                for mouse_event in mouse_motion.iter() {
-                    mouse_delta += mouse_event.delta;
-                }
+                controller.walk_speed
+            } * controller.speed_scale;
+            controller.velocity = axis_input.clamp_length_max(1.0) * max_speed;
+        } else {
+            let friction = controller.friction.clamp(0.0, 1.0);
+            controller.velocity *= 1.0 - friction;
+            if controller.velocity.length_squared() < 1e-6 {
+                controller.velocity = Vec3::ZERO;
             }
+        }
+        let forward = transform.forward();
+        let right = transform.right();
+        transform.translation += controller.velocity.x * dt * right
+            + controller.velocity.y * dt * Vec3::Y
+            + controller.velocity.z * dt * forward;
+
+        // cursor lock grabs the mouse for us while looking or panning
+        if !cursor.locked() {
+            continue;
+        }
+        let mouse_delta = actions.look();
+        if mouse_delta == Vec2::ZERO {
+            continue;
+        }
+
+        // pan, drag the view around at the pivot's depth
+        if actions.pressed(Action::EditorPan) {
+            let scale = controller.pan_sensitivity * controller.orbit_distance;
+            transform.translation +=
+                (-right * mouse_delta.x + transform.up() * mouse_delta.y) * scale;
+            continue;
+        }
+
+        if actions.pressed(Action::EditorLook) {
+            let pivot = transform.translation + forward * controller.orbit_distance;
+
+            let (mut yaw, mut pitch, _roll) = transform.rotation.to_euler(EulerRot::YXZ);
+            yaw -= mouse_delta.x * controller.sensitivity * time.delta_seconds();
+            pitch -= mouse_delta.y * controller.sensitivity * time.delta_seconds();
 
-            if mouse_delta != Vec2::ZERO {
-                let (mut yaw, mut pitch, _roll) = transform.rotation.to_euler(EulerRot::YXZ);
-                yaw -= mouse_delta.x * controller.sensitivity * time.delta_seconds();
-                pitch -= mouse_delta.y * controller.sensitivity * time.delta_seconds();
+            let pitch = pitch.clamp(-FRAC_PI_2, FRAC_PI_2);
+            transform.rotation = Quat::from_euler(EulerRot::YXZ, yaw, pitch, 0.0);
 
-                let pitch = pitch.clamp(-FRAC_PI_2, FRAC_PI_2);
-                transform.rotation = Quat::from_euler(EulerRot::YXZ, yaw, pitch, 0.0)
+            // orbit, same rotation but keep the pivot in the middle of the view
+            if actions.pressed(Action::EditorOrbit) {
+                transform.translation = pivot - transform.forward() * controller.orbit_distance;
             }
         }
     }
@@ -282,17 +388,10 @@ fn update_editor_camera(
 pub struct CameraPlayerConfig {
     pub disable_movement: bool,
     pub disable_look: bool,
-    pub height: f32,
-    pub sensitivity: f32,
-    pub key_forward: KeyCode,
-    pub key_back: KeyCode,
-    pub key_left: KeyCode,
-    pub key_right: KeyCode,
     pub walk_speed: f32,
     pub friction: f32,
     pub pitch: f32,
     pub yaw: f32,
-    pub velocity: Vec3,
 }
 
 impl Default for CameraPlayerConfig {
@@ -300,119 +399,29 @@ impl Default for CameraPlayerConfig {
         CameraPlayerConfig {
             disable_movement: false,
             disable_look: false,
-            height: 1.7,
-            sensitivity: 0.1,
-            key_forward: KeyCode::W,
-            key_back: KeyCode::S,
-            key_left: KeyCode::A,
-            key_right: KeyCode::D,
             walk_speed: 10.0,
             friction: 0.3,
             pitch: 0.0,
             yaw: 0.0,
-            velocity: Vec3::ZERO,
         }
     }
 }
 
 fn update_player_camera(
     time: Res<Time>,
-    mut mouse_motion: EventReader<MouseMotion>,
-    key_input: Res<Input<KeyCode>>,
+    actions: Res<ActionState>,
     mut query: Query<&mut Transform, With<CameraMain>>,
-    mut config: ResMut<CameraPlayerConfig>,
-    tlas: Res<Tlas>,
-    mut windows: ResMut<Windows>,
-    mut window_focus_events: EventReader<WindowFocused>,
-    mut cursor_left_events: EventReader<CursorLeft>,
-    room_config: Res<RoomConfig>,
+    config: Res<CameraPlayerConfig>,
+    settings: Res<Settings>,
+    cursor: Res<CursorLock>,
 ) {
-    let dt = time.delta_seconds();
-
-    // stop stealing cursor if window is unfocused
-    // TODO: this is last min hacks, find better way, but it kinda this works
-    if let Some(window) = windows.get_primary_mut() {
-        for _e in window_focus_events.iter() {
-            window.set_cursor_lock_mode(true);
-            window.set_cursor_visibility(false);
-        }
-
-        for _e in cursor_left_events.iter() {
-            window.set_cursor_lock_mode(false);
-            window.set_cursor_visibility(true);
-        }
-
-        if key_input.just_pressed(KeyCode::LAlt) {
-            window.set_cursor_lock_mode(false);
-            window.set_cursor_visibility(true);
-        } else if key_input.just_released(KeyCode::LAlt) {
-            window.set_cursor_lock_mode(true);
-            window.set_cursor_visibility(false);
-        } else if key_input.pressed(KeyCode::LAlt) {
-            return;
-        }
+    // mouse belongs to the os or the ui right now
+    if !cursor.locked() {
+        return;
     }
 
     for mut transform in query.iter_mut() {
-        // Handle key input
-        let right = transform.right();
-        let forward = -right.cross(Vec3::Y);
-
-        let mut axis_input = Vec3::ZERO;
-        if key_input.pressed(config.key_forward) && ray_check(&tlas, transform.translation, forward)
-        {
-            axis_input.z += 1.0;
-        }
-        if key_input.pressed(config.key_back) && ray_check(&tlas, transform.translation, -forward) {
-            axis_input.z -= 1.0;
-        }
-        if key_input.pressed(config.key_right) && ray_check(&tlas, transform.translation, right) {
-            axis_input.x += 1.0;
-        }
-        if key_input.pressed(config.key_left) && ray_check(&tlas, transform.translation, -right) {
-            axis_input.x -= 1.0;
-        }
-
-        // used for intro
-        if config.disable_movement {
-            axis_input = Vec3::ZERO;
-        }
-
-        // Apply movement update
-        if axis_input != Vec3::ZERO {
-            config.velocity = axis_input.normalize() * config.walk_speed;
-        } else {
-            let friction = config.friction.clamp(0.0, 1.0);
-            config.velocity *= 1.0 - friction;
-            if config.velocity.length_squared() < 1e-6 {
-                config.velocity = Vec3::ZERO;
-            }
-        }
-
-        transform.translation += config.velocity.x * dt * right
-            + config.velocity.y * dt * Vec3::Y
-            + config.velocity.z * dt * forward;
-
-        // TODO: Hack, keep user from falling into reactor
-        transform.translation.z = transform.translation.z.clamp(
-            -100.0,
-            room_config.reactor_center_z - room_config.reactor_radius,
-        );
-
-        // use tlas to find correct height
-        let mut ground_ray = Ray::new(transform.translation, -Vec3::Y);
-        if let Some(hit) = ground_ray.intersect_tlas(&tlas) {
-            let change = config.height - hit.distance;
-            transform.translation.y += change;
-        } else {
-            transform.translation.y -= 0.1;
-        }
-
-        // Handle mouse look on mouse button
-        let mut mouse_delta = Vec2::ZERO;
-        for mouse_event in mouse_motion.iter() {
-            mouse_delta += mouse_event.delta;
-        }
+        let mut mouse_delta = actions.look();
 
         // used for intro
         if config.disable_look {
@@ -421,21 +430,12 @@ fn update_player_camera(
 
         if mouse_delta != Vec2::ZERO {
             let (mut yaw, mut pitch, _roll) = transform.rotation.to_euler(EulerRot::YXZ);
-            yaw -= mouse_delta.x * config.sensitivity * time.delta_seconds();
-            pitch -= mouse_delta.y * config.sensitivity * time.delta_seconds();
+            let sensitivity = settings.input.mouse.sensitivity;
+            yaw -= mouse_delta.x * sensitivity * time.delta_seconds();
+            pitch -= mouse_delta.y * sensitivity * time.delta_seconds();
 
             let pitch = pitch.clamp(-FRAC_PI_2, FRAC_PI_2);
             transform.rotation = Quat::from_euler(EulerRot::YXZ, yaw, pitch, 0.0)
         }
     }
 }
-
-fn ray_check(tlas: &Tlas, origin: Vec3, direction: Vec3) -> bool {
-    let mut ray = Ray::new(origin, direction);
-    if let Some(hit) = ray.intersect_tlas(tlas) {
-        if hit.distance < 1.0 {
-            return false;
-        }
-    }
-    true
-}
@@ -0,0 +1,331 @@
+// kinematic capsule controller for the player camera, all collision is done with tlas rays
+use bevy::{math::vec3, prelude::*};
+use iyes_loopless::prelude::*;
+use sly_physics::prelude::*;
+
+use crate::{
+    actions::{Action, ActionState},
+    camera::{CameraMain, CameraPlayerConfig, CameraState},
+    cursor_lock::CursorLock,
+    prefabs::Held,
+    raycast::cast_through,
+    states::PauseState,
+};
+
+// how many times we try to slide along a wall before giving up for the frame
+const MAX_SLIDES: usize = 3;
+
+pub struct CharacterPlugin;
+
+impl Plugin for CharacterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            move_character
+                .run_in_state(CameraState::Player)
+                .run_in_state(PauseState::Running),
+        )
+        .add_exit_system(CameraState::Player, reset_character);
+    }
+}
+
+#[derive(Component)]
+pub struct CharacterController {
+    pub radius: f32,
+    // eye height above the feet, the camera sits at the top of the capsule
+    pub height: f32,
+    pub step_height: f32,
+    // max walkable slope in radians
+    pub max_slope: f32,
+    pub gravity: f32,
+    pub jump_speed: f32,
+    // gap kept between the capsule and whatever it hits
+    pub skin: f32,
+    pub velocity: Vec3,
+    pub grounded: bool,
+}
+
+impl Default for CharacterController {
+    fn default() -> Self {
+        Self {
+            radius: 0.3,
+            height: 1.7,
+            step_height: 0.3,
+            max_slope: 45.0_f32.to_radians(),
+            gravity: 20.0,
+            jump_speed: 6.0,
+            skin: 0.02,
+            velocity: Vec3::ZERO,
+            grounded: false,
+        }
+    }
+}
+
+impl CharacterController {
+    pub fn feet(&self, eye: Vec3) -> Vec3 {
+        eye - Vec3::Y * self.height
+    }
+}
+
+fn move_character(
+    time: Res<Time>,
+    actions: Res<ActionState>,
+    cursor: Res<CursorLock>,
+    mut query: Query<(&mut Transform, &mut CharacterController), With<CameraMain>>,
+    config: Res<CameraPlayerConfig>,
+    tlas: Res<Tlas>,
+    held_query: Query<(), With<Held>>,
+) {
+    let dt = time.delta_seconds();
+    if dt <= 0.0 {
+        return;
+    }
+
+    for (mut transform, mut controller) in query.iter_mut() {
+        let right = transform.right();
+        let forward = -right.cross(Vec3::Y);
+
+        // keys and left stick
+        let mut axis_input = actions.movement();
+        let mut jump = actions.just_pressed(Action::Jump);
+
+        // used for intro, and while the cursor is free nothing should walk us around
+        if config.disable_movement || !cursor.locked() {
+            axis_input = Vec2::ZERO;
+            jump = false;
+        }
+
+        // horizontal velocity
+        // not normalized so a half pushed stick walks slower
+        let wish_dir = (forward * axis_input.y + right * axis_input.x).clamp_length_max(1.0);
+        let mut horizontal = vec3(controller.velocity.x, 0.0, controller.velocity.z);
+        if wish_dir != Vec3::ZERO {
+            horizontal = wish_dir * config.walk_speed;
+        } else {
+            let friction = config.friction.clamp(0.0, 1.0);
+            horizontal *= 1.0 - friction;
+            if horizontal.length_squared() < 1e-6 {
+                horizontal = Vec3::ZERO;
+            }
+        }
+        controller.velocity.x = horizontal.x;
+        controller.velocity.z = horizontal.z;
+
+        // vertical velocity
+        if controller.grounded && jump {
+            controller.velocity.y = controller.jump_speed;
+            controller.grounded = false;
+        }
+        if !controller.grounded {
+            controller.velocity.y -= controller.gravity * dt;
+        }
+
+        // don't trip over whatever the tractor beam is carrying
+        let probe = Probe {
+            tlas: &tlas,
+            ignore: &held_query,
+        };
+
+        let mut feet = controller.feet(transform.translation);
+
+        // walls
+        feet = collide_and_slide(&probe, &controller, feet, horizontal * dt);
+
+        // ceiling
+        if controller.velocity.y > 0.0 {
+            let rise = controller.velocity.y * dt;
+            let head = feet + Vec3::Y * controller.height;
+            match probe.cast(head, Vec3::Y) {
+                Some(distance) if distance < rise + controller.skin => {
+                    feet.y += (distance - controller.skin).max(0.0);
+                    controller.velocity.y = 0.0;
+                }
+                _ => {
+                    feet.y += rise;
+                }
+            }
+        }
+
+        // ground, probe from step height so we walk up anything lower than it
+        let fall = (-controller.velocity.y * dt).max(0.0);
+        let snap = if controller.grounded {
+            controller.step_height
+        } else {
+            0.0
+        };
+        let probe_origin = feet + Vec3::Y * controller.step_height;
+        let reach = controller.step_height + fall.max(snap) + controller.skin;
+
+        match ground_probe(&probe, &controller, probe_origin) {
+            Some((distance, normal)) if distance <= reach && controller.velocity.y <= 0.0 => {
+                if normal.angle_between(Vec3::Y) <= controller.max_slope {
+                    feet.y = probe_origin.y - distance;
+                    controller.velocity.y = 0.0;
+                    controller.grounded = true;
+                } else {
+                    // too steep, slide down it
+                    controller.grounded = false;
+                    feet.y = (feet.y - fall).max(probe_origin.y - distance);
+                    let into_slope = controller.velocity.dot(normal);
+                    if into_slope < 0.0 {
+                        controller.velocity -= normal * into_slope;
+                    }
+                }
+            }
+            _ => {
+                controller.grounded = false;
+                feet.y -= fall;
+            }
+        }
+
+        transform.translation = feet + Vec3::Y * controller.height;
+    }
+}
+
+fn reset_character(mut query: Query<&mut CharacterController>) {
+    for mut controller in query.iter_mut() {
+        controller.velocity = Vec3::ZERO;
+        controller.grounded = false;
+    }
+}
+
+struct Probe<'a, 'w, 's> {
+    tlas: &'a Tlas,
+    ignore: &'a Query<'w, 's, (), With<Held>>,
+}
+
+impl<'a, 'w, 's> Probe<'a, 'w, 's> {
+    // distance to the first thing the ray hits, skipping ignored entities
+    fn cast(&self, origin: Vec3, direction: Vec3) -> Option<f32> {
+        cast_through(self.tlas, origin, direction, |e| self.ignore.contains(e))
+    }
+}
+
+// moves the capsule along motion, sliding along anything it hits
+fn collide_and_slide(
+    probe: &Probe,
+    controller: &CharacterController,
+    feet: Vec3,
+    motion: Vec3,
+) -> Vec3 {
+    let mut position = feet;
+    let mut remaining = motion;
+
+    for _ in 0..MAX_SLIDES {
+        let length = remaining.length();
+        if length < 1e-5 {
+            break;
+        }
+        let direction = remaining / length;
+
+        match sweep(probe, controller, position, direction) {
+            Some((distance, normal)) if distance - controller.radius < length => {
+                let allowed = (distance - controller.radius - controller.skin).max(0.0);
+                position += direction * allowed;
+                remaining = direction * (length - allowed);
+
+                // walls only push us sideways, the ground probe handles height
+                let normal = vec3(normal.x, 0.0, normal.z).normalize_or_zero();
+                if normal == Vec3::ZERO {
+                    break;
+                }
+                remaining -= normal * remaining.dot(normal);
+            }
+            _ => {
+                position += remaining;
+                break;
+            }
+        }
+    }
+
+    position
+}
+
+// casts a few rays at the edges of the capsule, returns the closest hit and its normal
+fn sweep(
+    probe: &Probe,
+    controller: &CharacterController,
+    feet: Vec3,
+    direction: Vec3,
+) -> Option<(f32, Vec3)> {
+    let side = Vec3::Y.cross(direction).normalize_or_zero() * controller.radius * 0.7;
+    let heights = [
+        controller.step_height + controller.skin,
+        controller.height * 0.5,
+        controller.height,
+    ];
+
+    let mut closest: Option<(f32, Vec3)> = None;
+    for height in heights {
+        for offset in [Vec3::ZERO, side, -side] {
+            let origin = feet + Vec3::Y * height + offset;
+            if let Some(distance) = probe.cast(origin, direction) {
+                if closest.map_or(true, |(d, _)| distance < d) {
+                    closest = Some((distance, origin));
+                }
+            }
+        }
+    }
+
+    closest.map(|(distance, origin)| (distance, surface_normal(probe, origin, direction, distance)))
+}
+
+// probes down from the center and around the capsule base so we don't drop into small gaps
+fn ground_probe(
+    probe: &Probe,
+    controller: &CharacterController,
+    origin: Vec3,
+) -> Option<(f32, Vec3)> {
+    let r = controller.radius * 0.7;
+    let mut closest: Option<(f32, Vec3)> = None;
+    for offset in [
+        Vec3::ZERO,
+        vec3(r, 0.0, 0.0),
+        vec3(-r, 0.0, 0.0),
+        vec3(0.0, 0.0, r),
+        vec3(0.0, 0.0, -r),
+    ] {
+        let start = origin + offset;
+        if let Some(distance) = probe.cast(start, -Vec3::Y) {
+            if closest.map_or(true, |(d, _)| distance < d) {
+                closest = Some((distance, start));
+            }
+        }
+    }
+
+    closest.map(|(distance, start)| (distance, surface_normal(probe, start, -Vec3::Y, distance)))
+}
+
+// hits only give us a distance, so rebuild the surface normal from two nearby rays
+fn surface_normal(probe: &Probe, origin: Vec3, direction: Vec3, distance: f32) -> Vec3 {
+    let fallback = -direction;
+    let eps = 0.05;
+
+    let u = if direction.y.abs() < 0.99 {
+        Vec3::Y.cross(direction)
+    } else {
+        Vec3::X.cross(direction)
+    }
+    .normalize();
+    let v = direction.cross(u);
+
+    let p0 = origin + direction * distance;
+    let cast = |offset: Vec3| {
+        probe
+            .cast(origin + offset, direction)
+            .map(|d| origin + offset + direction * d)
+    };
+    let (p1, p2) = match (cast(u * eps), cast(v * eps)) {
+        (Some(p1), Some(p2)) => (p1, p2),
+        _ => return fallback,
+    };
+
+    let normal = (p1 - p0).cross(p2 - p0).normalize_or_zero();
+    if normal == Vec3::ZERO {
+        fallback
+    } else if normal.dot(direction) > 0.0 {
+        -normal
+    } else {
+        normal
+    }
+}
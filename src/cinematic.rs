@@ -0,0 +1,218 @@
+// scripted camera paths, level scripts send StartCinematic and get CinematicFinished back
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_tweening::*;
+use iyes_loopless::prelude::*;
+
+use crate::{
+    actions::{Action, ActionState},
+    camera::{CameraMain, CameraState},
+    states::PauseState,
+    GameState,
+};
+
+pub struct CinematicPlugin;
+
+impl Plugin for CinematicPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Cinematic>()
+            .add_event::<StartCinematic>()
+            .add_event::<EndCinematic>()
+            .add_event::<CinematicFinished>()
+            .add_system(start_cinematic.run_in_state(GameState::Playing))
+            .add_system(
+                update_cinematic
+                    .run_in_state(GameState::Playing)
+                    .run_in_state(PauseState::Running)
+                    .run_in_state(CameraState::Cinematic),
+            )
+            .add_exit_system(CameraState::Cinematic, exit_cinematic);
+    }
+}
+
+// One stop along a camera path
+#[derive(Clone)]
+pub struct CameraKey {
+    pub position: Vec3,
+    pub look_at: Vec3,
+    // seconds to get here from the previous key
+    pub duration: f32,
+    pub ease: EaseFunction,
+}
+
+impl CameraKey {
+    pub fn new(position: Vec3, look_at: Vec3, duration: f32) -> Self {
+        Self {
+            position,
+            look_at,
+            duration,
+            ease: EaseFunction::SineInOut,
+        }
+    }
+
+    pub fn with_ease(mut self, ease: EaseFunction) -> Self {
+        self.ease = ease;
+        self
+    }
+
+    fn from_transform(trans: &Transform) -> Self {
+        Self::new(trans.translation, trans.translation + trans.forward(), 0.0)
+    }
+}
+
+// Keys are played in order starting from wherever the camera is
+#[derive(Clone, Default)]
+pub struct CameraPath {
+    pub keys: Vec<CameraKey>,
+    // fly back to where the camera started, so the player doesn't jump
+    pub return_to_start: bool,
+    // return duration when return_to_start is set
+    pub return_duration: f32,
+    // let the player skip with Action::SkipDialog
+    pub skippable: bool,
+}
+
+impl CameraPath {
+    pub fn duration(&self) -> f32 {
+        let keys = self.keys.iter().map(|k| k.duration).sum::<f32>();
+        if self.return_to_start {
+            keys + self.return_duration
+        } else {
+            keys
+        }
+    }
+}
+
+// Send to take the camera away from the player and play a path
+pub struct StartCinematic(pub CameraPath);
+
+// Send to stop the current path early, the camera jumps to the end
+pub struct EndCinematic;
+
+// Sent once a path is done or skipped and the player has control again
+pub struct CinematicFinished;
+
+#[derive(Default)]
+pub struct Cinematic {
+    timer: Timer,
+    end: Option<Transform>,
+    skippable: bool,
+    // camera to go back to, the editor camera stays the editor camera
+    previous: Option<CameraState>,
+}
+
+impl Cinematic {
+    pub fn playing(&self) -> bool {
+        self.end.is_some()
+    }
+}
+
+// Moves the camera between two keys, position and look target are eased separately
+struct CameraKeyLens {
+    start: CameraKey,
+    end: CameraKey,
+}
+
+impl Lens<Transform> for CameraKeyLens {
+    fn lerp(&mut self, target: &mut Transform, ratio: f32) {
+        let position = self.start.position.lerp(self.end.position, ratio);
+        let look_at = self.start.look_at.lerp(self.end.look_at, ratio);
+        *target = Transform::from_translation(position).looking_at(look_at, Vec3::Y);
+    }
+}
+
+fn start_cinematic(
+    mut commands: Commands,
+    mut start_events: EventReader<StartCinematic>,
+    camera_query: Query<(Entity, &Transform), With<CameraMain>>,
+    camera_state: Res<CurrentState<CameraState>>,
+    mut cinematic: ResMut<Cinematic>,
+) {
+    // only the last one sent this frame wins
+    let path = match start_events.iter().last() {
+        Some(StartCinematic(path)) => path,
+        None => return,
+    };
+    if path.keys.is_empty() {
+        return;
+    }
+    // the one playing owns the camera until it's done
+    if cinematic.playing() {
+        warn!("cinematic already playing, ignoring new one");
+        return;
+    }
+
+    let (camera_entity, camera_trans) = camera_query.single();
+    let start = CameraKey::from_transform(camera_trans);
+
+    let mut keys = path.keys.clone();
+    if path.return_to_start {
+        let mut back = start.clone();
+        back.duration = path.return_duration;
+        keys.push(back);
+    }
+
+    let mut tweens = Vec::new();
+    let mut previous = start;
+    for key in keys.iter() {
+        tweens.push(Tween::new(
+            key.ease,
+            TweeningType::Once,
+            Duration::from_secs_f32(key.duration.max(0.01)),
+            CameraKeyLens {
+                start: previous,
+                end: key.clone(),
+            },
+        ));
+        previous = key.clone();
+    }
+
+    let end = Transform::from_translation(previous.position).looking_at(previous.look_at, Vec3::Y);
+    *cinematic = Cinematic {
+        timer: Timer::from_seconds(path.duration(), false),
+        end: Some(end),
+        skippable: path.skippable,
+        previous: Some(camera_state.0.clone()),
+    };
+
+    commands
+        .entity(camera_entity)
+        .insert(Animator::new(Sequence::new(tweens)));
+    commands.insert_resource(NextState(CameraState::Cinematic));
+}
+
+fn update_cinematic(
+    mut commands: Commands,
+    mut cinematic: ResMut<Cinematic>,
+    mut end_events: EventReader<EndCinematic>,
+    actions: Res<ActionState>,
+    time: Res<Time>,
+) {
+    cinematic.timer.tick(time.delta());
+
+    let skipped = cinematic.skippable && actions.just_pressed(Action::SkipDialog);
+    if cinematic.timer.finished() || skipped || end_events.iter().count() > 0 {
+        let previous = cinematic.previous.clone().unwrap_or(CameraState::Player);
+        commands.insert_resource(NextState(previous));
+    }
+}
+
+fn exit_cinematic(
+    mut commands: Commands,
+    mut camera_query: Query<(Entity, &mut Transform), With<CameraMain>>,
+    mut cinematic: ResMut<Cinematic>,
+    mut finished_events: EventWriter<CinematicFinished>,
+) {
+    let (camera_entity, mut camera_trans) = camera_query.single_mut();
+    commands
+        .entity(camera_entity)
+        .remove::<Animator<Transform>>();
+
+    // snap to the end in case we were skipped or the tween was a frame behind
+    if let Some(end) = cinematic.end.take() {
+        *camera_trans = end;
+    }
+    *cinematic = Cinematic::default();
+    finished_events.send(CinematicFinished);
+}
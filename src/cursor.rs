@@ -1,10 +1,18 @@
 use bevy::{pbr::NotShadowCaster, prelude::*};
 use bevy_inspector_egui::{bevy_egui::EguiContext, prelude::*};
 use bevy_mod_outline::Outline;
-use iyes_loopless::state::CurrentState;
+use iyes_loopless::prelude::*;
 use sly_physics::prelude::*;
 
-use crate::{camera::CameraState, hide_window};
+use crate::{
+    actions::{Action, ActionState},
+    assets::{FontAssets, CLEAR},
+    camera::CameraState,
+    hide_window,
+    locale::Strings,
+    settings::Settings,
+    states::PauseState,
+};
 
 use super::Keep;
 
@@ -20,14 +28,52 @@ pub enum CursorInteraction {
     None,
 }
 
-pub struct CursorEvent(pub Entity);
+// Sent for whatever the cursor ray hit this frame
+pub struct CursorEvent {
+    pub entity: Entity,
+    pub distance: f32,
+}
 
-impl Default for InteractionTime {
-    fn default() -> Self {
+// Anything the player can use, CursorInteraction is added for you
+#[derive(Component)]
+pub struct Interactable {
+    // string table key for the prompt, "prompt.pick_up", "prompt.flip", "prompt.talk"
+    pub verb: String,
+    // max distance from the player camera, the editor camera ignores it
+    pub range: f32,
+    // key shown instead of the verb, can't be used while set
+    pub disabled: Option<String>,
+    // clicks are ignored until this finishes
+    pub cooldown: Timer,
+}
+
+impl Interactable {
+    pub fn new(verb: impl Into<String>) -> Self {
         Self {
-            timer: Timer::from_seconds(0.0, false),
+            verb: verb.into(),
+            range: 2.5,
+            disabled: None,
+            cooldown: Timer::from_seconds(0.0, false),
         }
     }
+
+    pub fn with_range(mut self, range: f32) -> Self {
+        self.range = range;
+        self
+    }
+
+    pub fn with_disabled(mut self, reason: impl Into<String>) -> Self {
+        self.disabled = Some(reason.into());
+        self
+    }
+
+    pub fn start_cooldown(&mut self, duration: std::time::Duration) {
+        self.cooldown = Timer::new(duration, false);
+    }
+
+    pub fn ready(&self) -> bool {
+        self.disabled.is_none() && self.cooldown.finished()
+    }
 }
 
 impl Plugin for CursorPlugin {
@@ -37,10 +83,27 @@ impl Plugin for CursorPlugin {
             .add_plugin(InspectorPlugin::<Inspector>::new())
             .add_startup_system(hide_window::<Inspector>)
             .add_startup_system(setup_cursor)
+            .add_system(add_cursor_interaction)
             .add_system(cursor_raycast)
-            .add_system(advance_interaction_timers.after(cursor_raycast))
+            .add_system(
+                advance_interaction_timers
+                    .run_in_state(PauseState::Running)
+                    .after(cursor_raycast),
+            )
             .add_system(clear_interactions.after(advance_interaction_timers))
-            .add_system(interaction_check.after(clear_interactions));
+            .add_system(
+                interaction_check
+                    .run_in_state(PauseState::Running)
+                    .after(clear_interactions),
+            )
+            .add_system(update_outlines.after(interaction_check))
+            .add_enter_system(CameraState::Player, setup_prompt)
+            .add_exit_system(CameraState::Player, cleanup_prompt)
+            .add_system(
+                update_prompt
+                    .run_in_state(CameraState::Player)
+                    .after(interaction_check),
+            );
     }
 }
 
@@ -50,6 +113,17 @@ pub struct Inspector {
     active: Option<Entity>,
 }
 
+impl Inspector {
+    // last entity clicked on, the editor camera frames it
+    pub fn active(&self) -> Option<Entity> {
+        self.active
+    }
+
+    pub fn select(&mut self, entity: Entity) {
+        self.active = Some(entity);
+    }
+}
+
 pub struct CursorConfig {
     pub hover: Color,
     pub clicked: Color,
@@ -133,7 +207,10 @@ fn cursor_raycast(
                 cursor_trans.translation = ray.origin + ray.direction * hit.distance;
                 cursor_vis.is_visible = true;
 
-                interaction_event.send(CursorEvent(hit.entity));
+                interaction_event.send(CursorEvent {
+                    entity: hit.entity,
+                    distance: hit.distance,
+                });
             } else {
                 cursor_vis.is_visible = false;
             }
@@ -141,48 +218,40 @@ fn cursor_raycast(
     }
 }
 
-// Used to limit when entity can be interacted with
-// TODO: I hate the name
-#[derive(Component)]
-pub struct InteractionTime {
-    pub timer: Timer,
+fn add_cursor_interaction(mut commands: Commands, query: Query<Entity, Added<Interactable>>) {
+    for e in query.iter() {
+        commands.entity(e).insert(CursorInteraction::None);
+    }
 }
 
-fn advance_interaction_timers(mut query: Query<&mut InteractionTime>, time: Res<Time>) {
-    for mut interaction_timer in query.iter_mut() {
-        interaction_timer.timer.tick(time.delta());
+fn advance_interaction_timers(mut query: Query<&mut Interactable>, time: Res<Time>) {
+    for mut interactable in query.iter_mut() {
+        interactable.cooldown.tick(time.delta());
     }
 }
 
-pub fn clear_interactions(mut query: Query<(&mut CursorInteraction, Option<&mut Outline>)>) {
-    for (mut interaction, outline) in query.iter_mut() {
+pub fn clear_interactions(mut query: Query<&mut CursorInteraction>) {
+    for mut interaction in query.iter_mut() {
         *interaction = CursorInteraction::None;
-        if let Some(mut outline) = outline {
-            outline.visible = false;
-        }
     }
 }
 
 pub fn interaction_check(
     mut cursor_events: EventReader<CursorEvent>,
-    mut query: Query<(&mut CursorInteraction, Option<&InteractionTime>)>,
-    mouse_input: Res<Input<MouseButton>>,
+    mut query: Query<(&mut CursorInteraction, &Interactable)>,
+    actions: Res<ActionState>,
     mut inspector: ResMut<Inspector>,
+    camera_state: Res<CurrentState<CameraState>>,
 ) {
     for event in cursor_events.iter() {
-        let mut clicked = mouse_input.just_pressed(MouseButton::Left);
-
         // see if the entity is interactable
-        if let Ok((mut interaction, interaction_time_maybe)) = query.get_mut(event.0) {
-            // ignore click if timer is active
-            if let Some(interaction_time) = interaction_time_maybe {
-                if !interaction_time.timer.finished() {
-                    clicked = false;
-                }
+        if let Ok((mut interaction, interactable)) = query.get_mut(event.entity) {
+            if camera_state.0 == CameraState::Player && event.distance > interactable.range {
+                continue;
             }
 
-            if clicked {
-                inspector.active = Some(event.0);
+            if actions.just_pressed(Action::Interact) && interactable.ready() {
+                inspector.active = Some(event.entity);
                 *interaction = CursorInteraction::Clicked;
             } else {
                 inspector.active = None;
@@ -191,3 +260,115 @@ pub fn interaction_check(
         }
     }
 }
+
+// outlines on the entity and its children follow the cursor
+fn update_outlines(
+    query: Query<(Entity, &Interactable, &CursorInteraction, Option<&Children>)>,
+    mut outline_query: Query<&mut Outline>,
+    cursor_config: Res<CursorConfig>,
+) {
+    for (e, interactable, interaction, children) in query.iter() {
+        let visible = !matches!(interaction, CursorInteraction::None);
+        let colour = if interactable.disabled.is_some() {
+            cursor_config.disabled
+        } else if matches!(interaction, CursorInteraction::Clicked) {
+            cursor_config.clicked
+        } else {
+            cursor_config.hover
+        };
+
+        let children = children.map(|c| c.iter().copied().collect::<Vec<_>>());
+        for target in std::iter::once(e).chain(children.into_iter().flatten()) {
+            if let Ok(mut outline) = outline_query.get_mut(target) {
+                if outline.visible != visible || (visible && outline.colour != colour) {
+                    outline.visible = visible;
+                    outline.colour = colour;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Component)]
+struct Prompt;
+
+#[derive(Component)]
+struct PromptRoot;
+
+fn setup_prompt(mut commands: Commands, font_assets: Res<FontAssets>) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Percent(100.0), Val::Auto),
+                position: UiRect {
+                    top: Val::Percent(56.0),
+                    ..default()
+                },
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            color: CLEAR.into(),
+            ..default()
+        })
+        .insert(PromptRoot)
+        .insert(Name::new("ui Prompt"))
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle {
+                    text: Text {
+                        sections: vec![font_assets.h1(String::new(), Color::WHITE)],
+                        alignment: Default::default(),
+                    },
+                    ..default()
+                })
+                .insert(Prompt);
+        });
+}
+
+fn cleanup_prompt(mut commands: Commands, query: Query<Entity, With<PromptRoot>>) {
+    for e in query.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+}
+
+// "[E] Flip" under the crosshair, or why it can't be used
+fn update_prompt(
+    query: Query<(&Interactable, &CursorInteraction)>,
+    mut prompt_query: Query<&mut Text, With<Prompt>>,
+    settings: Res<Settings>,
+    strings: Res<Strings>,
+    cursor_config: Res<CursorConfig>,
+) {
+    let (value, color) = match query
+        .iter()
+        .find(|(_, interaction)| !matches!(interaction, CursorInteraction::None))
+    {
+        Some((interactable, _)) => match &interactable.disabled {
+            Some(reason) => (strings.get(reason), cursor_config.disabled),
+            None => {
+                let key = settings
+                    .input
+                    .get(Action::Interact)
+                    .iter()
+                    .map(|b| b.label())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                let prompt = strings.format(
+                    "prompt.use",
+                    &[("key", key), ("verb", strings.get(&interactable.verb))],
+                );
+                (prompt, Color::WHITE)
+            }
+        },
+        None => (String::new(), Color::WHITE),
+    };
+
+    for mut text in prompt_query.iter_mut() {
+        let section = &mut text.sections[0];
+        if section.value != value {
+            section.value = value.clone();
+            section.style.color = color;
+        }
+    }
+}
@@ -0,0 +1,89 @@
+// single owner for cursor grab and visibility, nothing else should touch the window cursor
+use bevy::{
+    prelude::*,
+    window::{WindowFocused, Windows},
+};
+use bevy_inspector_egui::bevy_egui::EguiContext;
+use iyes_loopless::prelude::*;
+
+use crate::{
+    actions::{Action, ActionState, ActionSystem},
+    camera::CameraState,
+};
+
+pub struct CursorLockPlugin;
+
+impl Plugin for CursorLockPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CursorLock>()
+            .add_system_to_stage(CoreStage::PreUpdate, update_cursor_lock.after(ActionSystem));
+    }
+}
+
+// Anything with this frees the cursor while it exists, pause menus, popups and such
+#[derive(Component)]
+pub struct ReleaseCursor;
+
+pub struct CursorLock {
+    locked: bool,
+    window_focused: bool,
+    // force the window to be updated next frame, set when focus comes back
+    dirty: bool,
+}
+
+impl Default for CursorLock {
+    fn default() -> Self {
+        Self {
+            locked: false,
+            window_focused: true,
+            dirty: true,
+        }
+    }
+}
+
+impl CursorLock {
+    // true while the game owns the mouse, look input should be ignored otherwise
+    pub fn locked(&self) -> bool {
+        self.locked
+    }
+}
+
+fn update_cursor_lock(
+    actions: Res<ActionState>,
+    camera_state: Res<CurrentState<CameraState>>,
+    release_query: Query<(), With<ReleaseCursor>>,
+    mut egui_context: ResMut<EguiContext>,
+    mut window_focus_events: EventReader<WindowFocused>,
+    mut windows: ResMut<Windows>,
+    mut cursor: ResMut<CursorLock>,
+) {
+    for event in window_focus_events.iter() {
+        cursor.window_focused = event.focused;
+        // the os drops our grab when we lose focus, so apply it again
+        cursor.dirty = true;
+    }
+
+    let wants_lock = match camera_state.0 {
+        CameraState::Player => !actions.pressed(Action::UnlockCursor),
+        CameraState::Editor => actions.any_pressed([Action::EditorLook, Action::EditorPan]),
+        CameraState::Cinematic => true,
+        CameraState::Static => false,
+    };
+
+    let locked = wants_lock
+        && cursor.window_focused
+        && release_query.is_empty()
+        && !egui_context.ctx_mut().wants_pointer_input();
+
+    // only touch the window when something changed, setting it every frame flickers
+    if locked == cursor.locked && !cursor.dirty {
+        return;
+    }
+    cursor.locked = locked;
+    cursor.dirty = false;
+
+    if let Some(window) = windows.get_primary_mut() {
+        window.set_cursor_lock_mode(locked);
+        window.set_cursor_visibility(!locked);
+    }
+}
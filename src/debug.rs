@@ -7,8 +7,11 @@ use iyes_loopless::prelude::*;
 use sly_physics::prelude::*;
 
 use crate::{
-    assets::FontAssets, camera::*, cursor::Inspector, hide_window, show_window, GameState, Keep,
-    LevelState,
+    actions::{Action, ActionState},
+    assets::FontAssets,
+    camera::*,
+    cursor::Inspector,
+    hide_window, show_window, GameState, Keep, LevelState,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
@@ -57,11 +60,11 @@ impl Plugin for DebugPlugin {
 
 fn toggle_debug(
     mut commands: Commands,
-    input: Res<Input<KeyCode>>,
+    actions: Res<ActionState>,
     overlay_state: Res<CurrentState<Debug>>,
     mut world_inspector: ResMut<WorldInspectorParams>,
 ) {
-    if input.just_pressed(KeyCode::F1) {
+    if actions.just_pressed(Action::ToggleDebug) {
         match overlay_state.0 {
             Debug::Paused => {
                 commands.insert_resource(NextState(Debug::Running));
@@ -77,10 +80,10 @@ fn toggle_debug(
 
 fn toggle_physics_debug(
     mut commands: Commands,
-    input: Res<Input<KeyCode>>,
+    actions: Res<ActionState>,
     state: Res<CurrentState<PhysicsDebugState>>,
 ) {
-    if input.just_pressed(KeyCode::F2) {
+    if actions.just_pressed(Action::TogglePhysicsDebug) {
         match state.0 {
             PhysicsDebugState::Paused => {
                 commands.insert_resource(NextState(PhysicsDebugState::Running));
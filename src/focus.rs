@@ -0,0 +1,100 @@
+// keyboard and gamepad navigation for ui buttons, works alongside the mouse
+use bevy::{prelude::*, ui::UiSystem};
+
+use crate::{
+    actions::{Action, ActionState, ActionSystem},
+    cursor_lock::CursorLock,
+};
+
+pub struct FocusPlugin;
+
+impl Plugin for FocusPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Focus>().add_system_to_stage(
+            CoreStage::PreUpdate,
+            update_focus.after(UiSystem::Focus).after(ActionSystem),
+        );
+    }
+}
+
+// Button currently selected with the d-pad, None while the mouse is in charge
+#[derive(Default)]
+pub struct Focus {
+    pub entity: Option<Entity>,
+}
+
+fn update_focus(
+    actions: Res<ActionState>,
+    mut cursor_moved: EventReader<CursorMoved>,
+    mut focus: ResMut<Focus>,
+    cursor: Res<CursorLock>,
+    mut button_query: Query<
+        (
+            Entity,
+            &mut Interaction,
+            &GlobalTransform,
+            &ComputedVisibility,
+        ),
+        With<Button>,
+    >,
+) {
+    // mouse moved, hand control back to it, or we are playing and the buttons are out of reach
+    if cursor_moved.iter().count() > 0 || cursor.locked() {
+        focus.entity = None;
+        return;
+    }
+
+    // top to bottom, ui y goes up
+    let mut buttons = button_query
+        .iter()
+        .filter(|(.., visibility)| visibility.is_visible())
+        .map(|(e, _, trans, _)| (e, trans.translation()))
+        .collect::<Vec<_>>();
+    buttons.sort_by(|(_, a), (_, b)| b.y.total_cmp(&a.y).then(a.x.total_cmp(&b.x)));
+
+    // focused button went away, menu closed or changed
+    if let Some(e) = focus.entity {
+        if !buttons.iter().any(|(b, _)| *b == e) {
+            focus.entity = None;
+        }
+    }
+
+    let step = if actions.just_pressed(Action::MenuDown) {
+        1
+    } else if actions.just_pressed(Action::MenuUp) {
+        -1
+    } else {
+        0
+    };
+    if step != 0 && !buttons.is_empty() {
+        let count = buttons.len() as i32;
+        let next = match focus
+            .entity
+            .and_then(|e| buttons.iter().position(|(b, _)| *b == e))
+        {
+            Some(index) => (index as i32 + step).rem_euclid(count),
+            None if step > 0 => 0,
+            None => count - 1,
+        };
+        focus.entity = Some(buttons[next as usize].0);
+    }
+
+    let focused = match focus.entity {
+        Some(e) => e,
+        None => return,
+    };
+
+    // ui focus resets these every frame from the mouse, so set them again after it
+    for (e, mut interaction, ..) in button_query.iter_mut() {
+        let target = if e != focused {
+            Interaction::None
+        } else if actions.just_pressed(Action::MenuConfirm) {
+            Interaction::Clicked
+        } else {
+            Interaction::Hovered
+        };
+        if *interaction != target {
+            *interaction = target;
+        }
+    }
+}
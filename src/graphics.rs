@@ -0,0 +1,142 @@
+// window, msaa and fov from the player's settings, applied at startup and whenever they change
+use bevy::{
+    prelude::*,
+    window::{PresentMode, WindowMode},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{camera::CameraMain, settings::Settings};
+
+pub struct GraphicsPlugin;
+
+impl Plugin for GraphicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(apply_graphics);
+    }
+}
+
+// what the settings menu cycles through
+pub const RESOLUTIONS: [(u32, u32); 6] = [
+    (800, 600),
+    (1280, 720),
+    (1366, 768),
+    (1600, 900),
+    (1920, 1080),
+    (2560, 1440),
+];
+
+// wgpu only does 1 and 4 everywhere
+pub const MSAA_SAMPLES: [u32; 2] = [1, 4];
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisplayMode {
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
+
+impl DisplayMode {
+    pub fn window_mode(&self) -> WindowMode {
+        match self {
+            DisplayMode::Windowed => WindowMode::Windowed,
+            DisplayMode::Borderless => WindowMode::BorderlessFullscreen,
+            DisplayMode::Fullscreen => WindowMode::SizedFullscreen,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct GraphicsSettings {
+    pub mode: DisplayMode,
+    pub resolution: (u32, u32),
+    pub msaa: u32,
+    pub vsync: bool,
+    // vertical, in degrees
+    pub fov: f32,
+}
+
+impl Default for GraphicsSettings {
+    fn default() -> Self {
+        Self {
+            mode: DisplayMode::Windowed,
+            resolution: (800, 600),
+            msaa: 1,
+            vsync: true,
+            fov: 45.0,
+        }
+    }
+}
+
+impl GraphicsSettings {
+    pub fn present_mode(&self) -> PresentMode {
+        if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        }
+    }
+
+    pub fn msaa(&self) -> Msaa {
+        Msaa {
+            samples: if MSAA_SAMPLES.contains(&self.msaa) {
+                self.msaa
+            } else {
+                1
+            },
+        }
+    }
+
+    // main.rs builds the window from this, so there's no resize on the first frame
+    pub fn window_descriptor(&self) -> WindowDescriptor {
+        WindowDescriptor {
+            width: self.resolution.0 as f32,
+            height: self.resolution.1 as f32,
+            mode: self.mode.window_mode(),
+            present_mode: self.present_mode(),
+            ..default()
+        }
+    }
+}
+
+fn apply_graphics(
+    settings: Res<Settings>,
+    mut windows: ResMut<Windows>,
+    mut msaa: ResMut<Msaa>,
+    mut camera_query: Query<&mut Projection, With<CameraMain>>,
+    mut applied: Local<Option<GraphicsSettings>>,
+) {
+    let graphics = &settings.graphics;
+    let last = applied.as_ref();
+    if last == Some(graphics) {
+        return;
+    }
+
+    if let Some(window) = windows.get_primary_mut() {
+        if last.map(|l| l.mode) != Some(graphics.mode) {
+            window.set_mode(graphics.mode.window_mode());
+        }
+        // the web canvas follows the page instead
+        #[cfg(not(target_arch = "wasm32"))]
+        if last.map(|l| l.resolution) != Some(graphics.resolution) {
+            let (width, height) = graphics.resolution;
+            window.set_resolution(width as f32, height as f32);
+        }
+        if last.map(|l| l.vsync) != Some(graphics.vsync) {
+            window.set_present_mode(graphics.present_mode());
+        }
+    }
+
+    let samples = graphics.msaa().samples;
+    if msaa.samples != samples {
+        msaa.samples = samples;
+    }
+
+    for mut projection in camera_query.iter_mut() {
+        if let Projection::Perspective(perspective) = &mut *projection {
+            perspective.fov = graphics.fov.to_radians();
+        }
+    }
+
+    *applied = Some(graphics.clone());
+}
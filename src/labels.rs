@@ -0,0 +1,307 @@
+// ui text anchored to entities in the world, projected to the screen every frame
+use bevy::{math::vec2, prelude::*, ui::UiSystem};
+use iyes_loopless::prelude::*;
+use sly_physics::prelude::*;
+
+use crate::{
+    assets::{FontAssets, CLEAR},
+    camera::CameraMain,
+    cursor::CursorEvent,
+    locale::Strings,
+    prefabs::{Held, Pellet, AI},
+    raycast::cast_through,
+    GameState,
+};
+
+pub struct LabelPlugin;
+
+impl Plugin for LabelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(spawn_labels.run_in_state(GameState::Playing))
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                update_labels
+                    .run_in_state(GameState::Playing)
+                    .before(UiSystem::Flex),
+            );
+    }
+}
+
+// width of the box labels are centered in
+const LABEL_WIDTH: f32 = 200.0;
+const BAR_HEIGHT: f32 = 6.0;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum LabelMode {
+    Always,
+    // only while the cursor is on the entity
+    Hover,
+}
+
+#[derive(Component)]
+pub struct WorldLabel {
+    pub text: String,
+    // text is a string table key rather than what to show
+    pub translate: bool,
+    pub color: Color,
+    pub mode: LabelMode,
+    // world space, from the entity origin
+    pub offset: Vec3,
+    // starts fading at fade_distance, gone at max_distance
+    pub fade_distance: f32,
+    pub max_distance: f32,
+    // hide when something is between the camera and the label
+    pub occlude: bool,
+    // 0-1 fill for a bar under the text
+    pub bar: Option<f32>,
+    pub bar_color: Color,
+    // hidden no matter what, for things like speech bubbles with nothing to say
+    pub hidden: bool,
+}
+
+impl WorldLabel {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            translate: false,
+            color: Color::WHITE,
+            mode: LabelMode::Always,
+            offset: Vec3::Y * 0.3,
+            fade_distance: 4.0,
+            max_distance: 6.0,
+            occlude: true,
+            bar: None,
+            bar_color: Color::WHITE,
+            hidden: false,
+        }
+    }
+
+    // follows the language, for names of things rather than text made up as the game runs
+    pub fn translated(key: impl Into<String>) -> Self {
+        Self {
+            translate: true,
+            ..Self::new(key)
+        }
+    }
+
+    pub fn hover(mut self) -> Self {
+        self.mode = LabelMode::Hover;
+        self
+    }
+
+    pub fn with_offset(mut self, offset: Vec3) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn with_distance(mut self, fade_distance: f32, max_distance: f32) -> Self {
+        self.fade_distance = fade_distance;
+        self.max_distance = max_distance;
+        self
+    }
+
+    pub fn with_bar(mut self, value: f32, color: Color) -> Self {
+        self.bar = Some(value);
+        self.bar_color = color;
+        self
+    }
+}
+
+// ui root following a labeled entity
+#[derive(Component)]
+struct LabelUi(Entity);
+
+#[derive(Component)]
+struct LabelText;
+
+#[derive(Component)]
+struct LabelBar;
+
+#[derive(Component)]
+struct LabelBarFill;
+
+fn spawn_labels(
+    mut commands: Commands,
+    query: Query<Entity, Added<WorldLabel>>,
+    font_assets: Res<FontAssets>,
+) {
+    for e in query.iter() {
+        commands
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    size: Size::new(Val::Px(LABEL_WIDTH), Val::Auto),
+                    flex_direction: FlexDirection::ColumnReverse,
+                    align_items: AlignItems::Center,
+                    display: Display::None,
+                    ..default()
+                },
+                color: CLEAR.into(),
+                ..default()
+            })
+            .insert(LabelUi(e))
+            .insert(Name::new("ui Label"))
+            .with_children(|parent| {
+                parent
+                    .spawn_bundle(TextBundle {
+                        text: Text {
+                            sections: vec![font_assets.sub_title(String::new(), Color::WHITE)],
+                            alignment: Default::default(),
+                        },
+                        ..default()
+                    })
+                    .insert(LabelText);
+
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(LABEL_WIDTH * 0.4), Val::Px(BAR_HEIGHT)),
+                            margin: UiRect::all(Val::Px(2.0)),
+                            display: Display::None,
+                            ..default()
+                        },
+                        color: Color::rgba(0.0, 0.0, 0.0, 0.5).into(),
+                        ..default()
+                    })
+                    .insert(LabelBar)
+                    .with_children(|parent| {
+                        parent
+                            .spawn_bundle(NodeBundle {
+                                style: Style {
+                                    size: Size::new(Val::Percent(0.0), Val::Percent(100.0)),
+                                    ..default()
+                                },
+                                ..default()
+                            })
+                            .insert(LabelBarFill);
+                    });
+            });
+    }
+}
+
+fn update_labels(
+    mut commands: Commands,
+    mut cursor_events: EventReader<CursorEvent>,
+    label_query: Query<(&WorldLabel, &GlobalTransform)>,
+    camera_query: Query<(&Camera, &Transform), With<CameraMain>>,
+    mut ui_query: Query<(Entity, &LabelUi, &mut Style, &Children)>,
+    mut text_query: Query<&mut Text, With<LabelText>>,
+    mut bar_query: Query<(&mut Style, &mut UiColor, &Children), (With<LabelBar>, Without<LabelUi>)>,
+    mut fill_query: Query<
+        (&mut Style, &mut UiColor),
+        (With<LabelBarFill>, Without<LabelUi>, Without<LabelBar>),
+    >,
+    ignore_query: Query<(), Or<(With<Held>, With<Pellet>, With<AI>)>>,
+    windows: Res<Windows>,
+    tlas: Res<Tlas>,
+    strings: Res<Strings>,
+) {
+    let hovered = cursor_events.iter().map(|e| e.entity).collect::<Vec<_>>();
+
+    // camera moves in PostUpdate, so use its transform rather than last frame's global
+    let (camera, camera_trans) = match camera_query.get_single() {
+        Ok(camera) => camera,
+        Err(_) => return,
+    };
+    let camera_global = GlobalTransform::from(*camera_trans);
+    let window = windows
+        .get_primary()
+        .map_or(Vec2::ZERO, |w| vec2(w.width(), w.height()));
+
+    for (ui_entity, label_ui, mut style, children) in ui_query.iter_mut() {
+        let (label, trans) = match label_query.get(label_ui.0) {
+            Ok(result) => result,
+            Err(_) => {
+                // target was despawned or lost its label
+                commands.entity(ui_entity).despawn_recursive();
+                continue;
+            }
+        };
+
+        let anchor = trans.translation() + label.offset;
+        let to_anchor = anchor - camera_trans.translation;
+        let distance = to_anchor.length();
+
+        let mut visible = !label.hidden
+            && distance < label.max_distance
+            && (label.mode == LabelMode::Always || hovered.contains(&label_ui.0));
+
+        // walls and props hide it, not the entity itself or small things floating in the way
+        if visible && label.occlude && distance > 0.0 {
+            let hit = cast_through(&tlas, camera_trans.translation, to_anchor / distance, |e| {
+                e == label_ui.0 || ignore_query.contains(e)
+            });
+            if matches!(hit, Some(hit) if hit < distance - 0.05) {
+                visible = false;
+            }
+        }
+
+        let screen = camera.world_to_viewport(&camera_global, anchor);
+        let screen = match screen {
+            Some(screen)
+                if visible
+                    && screen.x >= 0.0
+                    && screen.x <= window.x
+                    && screen.y >= 0.0
+                    && screen.y <= window.y =>
+            {
+                screen
+            }
+            _ => {
+                if style.display != Display::None {
+                    style.display = Display::None;
+                }
+                continue;
+            }
+        };
+
+        style.display = Display::Flex;
+        style.position = UiRect {
+            left: Val::Px(screen.x - LABEL_WIDTH * 0.5),
+            bottom: Val::Px(screen.y),
+            ..default()
+        };
+
+        let fade_range = (label.max_distance - label.fade_distance).max(0.01);
+        let alpha = 1.0 - ((distance - label.fade_distance) / fade_range).clamp(0.0, 1.0);
+        let fade = |color: Color| {
+            let mut color = color;
+            color.set_a(color.a() * alpha);
+            color
+        };
+
+        for &child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                let section = &mut text.sections[0];
+                let value = if label.translate {
+                    strings.get(&label.text)
+                } else {
+                    label.text.clone()
+                };
+                if section.value != value {
+                    section.value = value;
+                }
+                section.style.color = fade(label.color);
+            }
+
+            if let Ok((mut bar_style, mut bar_color, bar_children)) = bar_query.get_mut(child) {
+                let value = match label.bar {
+                    Some(value) => value,
+                    None => {
+                        bar_style.display = Display::None;
+                        continue;
+                    }
+                };
+                bar_style.display = Display::Flex;
+                bar_color.0 = fade(Color::rgba(0.0, 0.0, 0.0, 0.5));
+
+                for &fill in bar_children.iter() {
+                    if let Ok((mut fill_style, mut fill_color)) = fill_query.get_mut(fill) {
+                        fill_style.size.width = Val::Percent(value.clamp(0.0, 1.0) * 100.0);
+                        fill_color.0 = fade(label.bar_color);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,12 +1,16 @@
 use std::f32::consts::*;
 
+use crate::assets::DialogueAssets;
 use crate::camera::CameraMain;
+use crate::cinematic::{CameraKey, CameraPath, StartCinematic};
 use crate::prefabs::*;
+use crate::save::{SaveSlots, Tool};
 use crate::LevelState;
 
 use bevy::math::vec3;
 use bevy::prelude::*;
 use iyes_loopless::prelude::*;
+use sly_physics::prelude::*;
 
 pub struct IntroPlugin;
 
@@ -14,15 +18,57 @@ impl Plugin for IntroPlugin {
     fn build(&self, app: &mut App) {
         app.add_enter_system(LevelState::Intro, spawn_training_room)
             .add_enter_system(LevelState::Intro, spawn_reactor_room)
-            .add_enter_system(LevelState::Intro, setup);
+            .add_enter_system(LevelState::Intro, setup)
+            .add_enter_system(LevelState::Intro, fly_through);
         //.add_exit_system(LevelState::Intro, cleanup);
     }
 }
 
-pub fn setup(mut commands: Commands, mut camera_query: Query<&mut Transform, With<CameraMain>>) {
+const PLAYER_START: Vec3 = Vec3::new(-1.0, 1.7, -3.0);
+const PLAYER_LOOK: Vec3 = Vec3::new(-1.0, 1.5, 0.0);
+// roughly the space kit barrel, its model sits on its origin
+const BARREL_SIZE: Vec3 = Vec3::new(0.5, 0.7, 0.5);
+
+pub fn setup(
+    mut commands: Commands,
+    mut camera_query: Query<&mut Transform, With<CameraMain>>,
+    room_config: Res<RoomConfig>,
+    dialogue_assets: Res<DialogueAssets>,
+    saves: Res<SaveSlots>,
+    mut collider_resources: ResMut<ColliderResources>,
+) {
+    // been through the tutorial before, hand everything over straight away
+    let tutorial_complete = matches!(saves.current(), Some(save) if save.tutorial_complete);
+    let blaster_unlocked = matches!(saves.current(), Some(save) if save.has_tool(Tool::Blaster));
+
     let mut camera_trans = camera_query.single_mut();
-    camera_trans.translation = vec3(-1.0, 1.7, -3.0);
-    camera_trans.look_at(vec3(-1.0, 1.5, 0.0), Vec3::Y);
+    camera_trans.translation = PLAYER_START;
+    camera_trans.look_at(PLAYER_LOOK, Vec3::Y);
+
+    commands
+        .spawn_bundle(SpatialBundle {
+            transform: *camera_trans,
+            ..default()
+        })
+        .insert(RespawnPoint)
+        .insert(Name::new("Respawn Point"));
+
+    // landing outside the training room
+    let landing_center = room_config.intro_floor_half + room_config.landing_floor_size.y * 0.5;
+    commands
+        .spawn_bundle(SpatialBundle {
+            transform: Transform::from_xyz(0.0, 1.7, landing_center)
+                .looking_at(vec3(0.0, 1.7, room_config.reactor_center_z), Vec3::Y),
+            ..default()
+        })
+        .insert(Checkpoint {
+            size: vec3(
+                room_config.landing_floor_size.x,
+                room_config.wall_height,
+                room_config.landing_floor_size.y,
+            ),
+        })
+        .insert(Name::new("Landing Checkpoint"));
 
     commands
         .spawn_bundle(SpatialBundle {
@@ -39,7 +85,8 @@ pub fn setup(mut commands: Commands, mut camera_query: Query<&mut Transform, Wit
             transform: Transform::from_xyz(0.0, 1.0, 0.0),
             ..default()
         })
-        .insert(AI::Intro);
+        .insert(AI::Intro)
+        .insert(DialogueRunner::new(dialogue_assets.intro.clone()).with_skip(tutorial_complete));
 
     // right of door
     commands
@@ -79,7 +126,11 @@ pub fn setup(mut commands: Commands, mut camera_query: Query<&mut Transform, Wit
             },
             ..default()
         })
-        .insert(PolarityBlaster::Disabled);
+        .insert(if blaster_unlocked {
+            PolarityBlaster::Enabled
+        } else {
+            PolarityBlaster::Disabled
+        });
 
     // left of door
     commands
@@ -115,4 +166,59 @@ pub fn setup(mut commands: Commands, mut camera_query: Query<&mut Transform, Wit
             ..default()
         })
         .insert(SpaceKit::Barrel(Barrel::Multiple));
+
+    // loose barrels, something other than pellets for the tractor beam
+    for translation in [vec3(5.0, 0.4, -0.5), vec3(5.5, 0.4, 0.5)] {
+        commands
+            .spawn_bundle(SpatialBundle {
+                transform: Transform::from_translation(translation),
+                ..default()
+            })
+            .insert_bundle(RigidBodyBundle {
+                collider: collider_resources.add_box(BARREL_SIZE),
+                mass: Mass(2.0),
+                ..default()
+            })
+            .insert(Grabbable)
+            .insert(Name::new("Barrel"))
+            .with_children(|parent| {
+                parent
+                    .spawn_bundle(SpatialBundle {
+                        transform: Transform::from_xyz(0.0, -BARREL_SIZE.y * 0.5, 0.0),
+                        ..default()
+                    })
+                    .insert(SpaceKit::Barrel(Barrel::Normal));
+            });
+    }
+}
+
+// establishing shot, down the reactor, back over the landing and into the training room
+fn fly_through(
+    mut cinematic_events: EventWriter<StartCinematic>,
+    room_config: Res<RoomConfig>,
+    saves: Res<SaveSlots>,
+) {
+    if matches!(saves.current(), Some(save) if save.tutorial_complete) {
+        return;
+    }
+
+    let reactor = vec3(
+        0.0,
+        room_config.wall_height_half,
+        room_config.reactor_center_z,
+    );
+    let reactor_end = room_config.reactor_length * 0.4;
+    let landing = room_config.intro_floor_half + room_config.landing_floor_size.y * 0.5;
+
+    cinematic_events.send(StartCinematic(CameraPath {
+        keys: vec![
+            CameraKey::new(reactor + vec3(-reactor_end, 3.0, 0.0), reactor, 0.0),
+            CameraKey::new(reactor + vec3(reactor_end, 1.0, -3.0), reactor, 6.0),
+            CameraKey::new(vec3(0.0, 2.5, landing), reactor, 3.0),
+            CameraKey::new(vec3(0.0, 3.0, -5.0), PLAYER_LOOK, 3.0),
+            CameraKey::new(PLAYER_START, PLAYER_LOOK, 1.5),
+        ],
+        skippable: true,
+        ..default()
+    }));
 }
@@ -3,15 +3,32 @@
 #![allow(dead_code)]
 #![allow(clippy::too_many_arguments)]
 
+mod actions;
 mod assets;
 mod camera;
+mod character;
+mod cinematic;
 mod cursor;
+mod cursor_lock;
 mod debug;
+mod focus;
+pub mod graphics;
+mod labels;
 mod level;
+mod locale;
+mod mixer;
+mod music;
 mod prefabs;
+mod raycast;
+mod save;
+pub mod settings;
+mod spatial_audio;
 mod states;
+mod subtitles;
+mod widgets;
 
 use crate::states::*;
+use actions::ActionPlugin;
 use assets::ButtonColors;
 use bevy::prelude::*;
 //use bevy_hanabi::HanabiPlugin;
@@ -20,12 +37,25 @@ use bevy_kira_audio::AudioPlugin;
 use bevy_mod_outline::OutlinePlugin;
 use bevy_tweening::TweeningPlugin;
 use camera::CameraPlugin;
+use character::CharacterPlugin;
+use cinematic::CinematicPlugin;
 use cursor::CursorPlugin;
+use cursor_lock::CursorLockPlugin;
 use debug::DebugPlugin;
+use focus::FocusPlugin;
+use graphics::GraphicsPlugin;
 use iyes_loopless::prelude::*;
+use labels::LabelPlugin;
 use level::LevelPlugin;
+use locale::LocalePlugin;
+use mixer::MixerPlugin;
+use music::MusicPlugin;
 use prefabs::PrefabPlugin;
+use save::SavePlugin;
+use settings::SettingsPlugin;
 use sly_physics::prelude::*;
+use spatial_audio::SpatialAudioPlugin;
+use subtitles::SubtitlePlugin;
 
 #[derive(Clone, Eq, PartialEq, Debug, Hash)]
 enum GameState {
@@ -33,9 +63,11 @@ enum GameState {
     Loading,    // load rest of the assets
     Menu,
     Playing,
+    Credits,
+    LoadFailed, // an asset failed to load, shows which one
 }
 
-#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+#[derive(Clone, Eq, PartialEq, Debug, Hash, serde::Serialize, serde::Deserialize)]
 pub enum LevelState {
     None,
     Intro,
@@ -53,6 +85,7 @@ impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
         app.add_loopless_state(GameState::PreLoading)
             .add_loopless_state(LevelState::None)
+            .add_loopless_state(PauseState::Running)
             .add_plugin(WorldInspectorPlugin::default())
             .insert_resource(WorldInspectorParams {
                 enabled: false,
@@ -68,9 +101,23 @@ impl Plugin for GamePlugin {
             .add_plugin(PhysicsDebugPlugin)
             .add_plugin(PhysicsBvhCameraPlugin)
             // local plugins
+            .add_plugin(SettingsPlugin)
+            .add_plugin(LocalePlugin)
+            .add_plugin(SavePlugin)
+            .add_plugin(GraphicsPlugin)
+            .add_plugin(MixerPlugin)
+            .add_plugin(SpatialAudioPlugin)
+            .add_plugin(MusicPlugin)
+            .add_plugin(ActionPlugin)
             .add_plugin(CameraPlugin)
+            .add_plugin(CharacterPlugin)
+            .add_plugin(CinematicPlugin)
             .add_plugin(CursorPlugin)
+            .add_plugin(CursorLockPlugin)
+            .add_plugin(FocusPlugin)
+            .add_plugin(LabelPlugin)
             .add_plugin(PrefabPlugin)
+            .add_plugin(SubtitlePlugin)
             // game states
             .add_plugin(StatePlugin)
             .add_plugin(LevelPlugin)
@@ -0,0 +1,250 @@
+// player facing text, looked up by key in the string table for the picked language
+// anything missing from a table falls back to english, tables live in assets/locale
+use std::{collections::HashMap, path::PathBuf};
+
+use bevy::{
+    asset::{AssetLoader, AssetPath, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assets::{FontAssets, LocaleAssets},
+    settings::Settings,
+};
+
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<StringTable>()
+            .init_asset_loader::<StringTableLoader>()
+            .init_resource::<Strings>()
+            .add_system(update_strings)
+            .add_system(update_translations.after(update_strings))
+            .add_system(update_fonts.after(update_strings));
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Spanish];
+
+    // in the language itself, so players can find theirs whatever is picked
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Spanish => "Español",
+        }
+    }
+}
+
+// Every string for one language, keyed by where it's used
+#[derive(Deserialize, TypeUuid)]
+#[uuid = "5d2b8e4f-1a7c-4f3e-9b6d-0c8a2e4f6b1d"]
+pub struct StringTable {
+    // for scripts fira sans doesn't cover, used for all text while the language is picked
+    #[serde(default)]
+    pub font: Option<String>,
+    pub strings: HashMap<String, String>,
+    // loaded with the table from font
+    #[serde(skip)]
+    font_handle: Option<Handle<Font>>,
+}
+
+#[derive(Default)]
+pub struct StringTableLoader;
+
+impl AssetLoader for StringTableLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let mut table = ron::de::from_bytes::<StringTable>(bytes)?;
+            let font = table
+                .font
+                .as_ref()
+                .map(|path| AssetPath::new(PathBuf::from(path), None));
+            table.font_handle = font.clone().map(|path| load_context.get_handle(path));
+
+            let mut asset = LoadedAsset::new(table);
+            if let Some(path) = font {
+                asset = asset.with_dependency(path);
+            }
+            load_context.set_default_asset(asset);
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["strings.ron"]
+    }
+}
+
+// The picked language's strings over the english ones, rebuilt when the language changes
+#[derive(Default)]
+pub struct Strings {
+    // None until the tables have loaded
+    language: Option<Language>,
+    strings: HashMap<String, String>,
+    font: Option<Handle<Font>>,
+}
+
+impl Strings {
+    // a missing key shows up as itself, easy to spot in game
+    pub fn get(&self, key: &str) -> String {
+        match self.strings.get(key) {
+            Some(text) => text.clone(),
+            None => key.to_string(),
+        }
+    }
+
+    // {name} in the string is replaced with the argument of that name
+    pub fn format(&self, key: &str, args: &[(&str, String)]) -> String {
+        let mut text = self.get(key);
+        for (name, value) in args {
+            text = text.replace(&format!("{{{}}}", name), value);
+        }
+        text
+    }
+}
+
+// Something with a string table entry, buttons mostly
+pub trait LocaleKey {
+    fn key(&self) -> &'static str;
+}
+
+// Fills the first text section from the string table, and again whenever the language changes
+#[derive(Component, Clone)]
+pub struct Translate {
+    key: String,
+    args: Vec<(&'static str, String)>,
+}
+
+impl Translate {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            args: Vec::new(),
+        }
+    }
+
+    pub fn with_arg(mut self, name: &'static str, value: impl ToString) -> Self {
+        self.set_arg(name, value);
+        self
+    }
+
+    pub fn set_arg(&mut self, name: &'static str, value: impl ToString) {
+        let value = value.to_string();
+        match self.args.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, v)) => *v = value,
+            None => self.args.push((name, value)),
+        }
+    }
+
+    pub fn text(&self, strings: &Strings) -> String {
+        strings.format(&self.key, &self.args)
+    }
+}
+
+fn update_strings(
+    mut strings: ResMut<Strings>,
+    settings: Res<Settings>,
+    locale_assets: Option<Res<LocaleAssets>>,
+    tables: Res<Assets<StringTable>>,
+    mut table_events: EventReader<AssetEvent<StringTable>>,
+    font_assets: Option<ResMut<FontAssets>>,
+) {
+    let locale_assets = match locale_assets {
+        Some(locale_assets) => locale_assets,
+        None => return,
+    };
+    // hot reloading a table shows the changes straight away
+    let reloaded = table_events.iter().count() > 0;
+    if !reloaded && strings.language == Some(settings.language) {
+        return;
+    }
+
+    // not loaded yet, try again next frame
+    let english = match tables.get(&locale_assets.en) {
+        Some(table) => table,
+        None => return,
+    };
+    let mut map = english.strings.clone();
+    let mut font = english.font_handle.clone();
+    if settings.language != Language::English {
+        let table = match tables.get(locale_assets.table(settings.language)) {
+            Some(table) => table,
+            None => return,
+        };
+        let missing = map
+            .keys()
+            .filter(|key| !table.strings.contains_key(*key))
+            .count();
+        if missing > 0 {
+            info!(
+                "{} strings missing from {:?}, using english for them",
+                missing, settings.language
+            );
+        }
+        map.extend(table.strings.clone());
+        font = table.font_handle.clone();
+    }
+
+    *strings = Strings {
+        language: Some(settings.language),
+        strings: map,
+        font,
+    };
+
+    if let Some(mut font_assets) = font_assets {
+        let font = match &strings.font {
+            Some(font) => font.clone(),
+            None => font_assets.fira_sans.clone(),
+        };
+        font_assets.text = font;
+    }
+}
+
+fn update_translations(
+    strings: Res<Strings>,
+    mut text_query: Query<(&Translate, &mut Text, ChangeTrackers<Translate>)>,
+) {
+    for (translate, mut text, tracker) in text_query.iter_mut() {
+        if !strings.is_changed() && !tracker.is_changed() {
+            continue;
+        }
+        if let Some(section) = text.sections.first_mut() {
+            section.value = translate.text(&strings);
+        }
+    }
+}
+
+// text already on screen picks up the new language's font, new text gets it from FontAssets
+fn update_fonts(
+    strings: Res<Strings>,
+    font_assets: Option<Res<FontAssets>>,
+    mut text_query: Query<&mut Text>,
+) {
+    let font_assets = match font_assets {
+        Some(font_assets) if strings.is_changed() => font_assets,
+        _ => return,
+    };
+    for mut text in text_query.iter_mut() {
+        for section in text.sections.iter_mut() {
+            if section.style.font != font_assets.text {
+                section.style.font = font_assets.text.clone();
+            }
+        }
+    }
+}
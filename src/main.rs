@@ -1,25 +1,25 @@
 // disable console on windows for release builds
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use bevy::prelude::{App, ClearColor, Color, Msaa, NonSend, WindowDescriptor};
+use bevy::prelude::{App, ClearColor, Color, NonSend, WindowDescriptor};
 use bevy::window::WindowId;
 use bevy::winit::WinitWindows;
 use bevy::DefaultPlugins;
-use reactor::GamePlugin;
+use reactor::{settings::Settings, GamePlugin};
 use std::io::Cursor;
 use winit::window::Icon;
 
 fn main() {
+    // window has to be right before it opens, GamePlugin keeps it in step after that
+    let graphics = Settings::load().graphics;
     App::new()
-        .insert_resource(Msaa { samples: 1 })
+        .insert_resource(graphics.msaa())
         .insert_resource(ClearColor(Color::rgb(0.4, 0.4, 0.4)))
         .insert_resource(WindowDescriptor {
-            width: 800.,
-            height: 600.,
             title: "Game Jam #2".to_string(), // TODO: use a better title
             canvas: Some("#bevy".to_owned()),
             fit_canvas_to_parent: true,
-            ..Default::default()
+            ..graphics.window_descriptor()
         })
         .add_plugins(DefaultPlugins)
         .add_plugin(GamePlugin)
@@ -0,0 +1,201 @@
+// volume for every audio channel goes through here, player levels from settings times a fixed
+// gain per bus, with music ducked while someone is talking
+use std::{any::TypeId, time::Duration};
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_kira_audio::prelude::*;
+use iyes_loopless::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{settings::Settings, spatial_audio::ChannelEmitter, states::PauseState, GameState};
+
+pub struct MixerPlugin;
+
+impl Plugin for MixerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Mixer>()
+            .add_system_to_stage(CoreStage::PostUpdate, update_mixer.label(MixerSystem));
+    }
+}
+
+#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MixerSystem;
+
+// music level while a voice line plays
+const DUCK_LEVEL: f32 = 0.35;
+// per second
+const DUCK_SPEED: f32 = 3.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bus {
+    Music,
+    Voice,
+    Sfx,
+}
+
+impl Bus {
+    // mix baked in so 1.0 on every slider sounds right
+    fn gain(&self) -> f32 {
+        match self {
+            Bus::Music => 0.3,
+            Bus::Voice => 0.4,
+            Bus::Sfx => 0.4,
+        }
+    }
+}
+
+// Player volume levels, 0-1
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct MixerSettings {
+    pub master: f32,
+    pub music: f32,
+    pub voice: f32,
+    pub sfx: f32,
+}
+
+impl Default for MixerSettings {
+    fn default() -> Self {
+        Self {
+            master: 1.0,
+            music: 1.0,
+            voice: 1.0,
+            sfx: 1.0,
+        }
+    }
+}
+
+impl MixerSettings {
+    pub fn level(&self, bus: Bus) -> f32 {
+        match bus {
+            Bus::Music => self.music,
+            Bus::Voice => self.voice,
+            Bus::Sfx => self.sfx,
+        }
+    }
+}
+
+// Audio channel markers say which bus they play on
+pub trait MixerChannel: Resource {
+    const BUS: Bus;
+    // part of a level, paused with the game and stopped when leaving it
+    const IN_GAME: bool = true;
+}
+
+pub trait MixerApp {
+    // add_audio_channel, with the channel volume kept in step with the mixer
+    fn add_mixer_channel<T: MixerChannel>(&mut self) -> &mut Self;
+}
+
+impl MixerApp for App {
+    fn add_mixer_channel<T: MixerChannel>(&mut self) -> &mut Self {
+        self.add_audio_channel::<T>()
+            .add_system_to_stage(CoreStage::PostUpdate, apply_volume::<T>.after(MixerSystem));
+        if T::IN_GAME {
+            self.add_enter_system(PauseState::Paused, pause_with_fade::<T>)
+                .add_exit_system(PauseState::Paused, resume_with_fade::<T>)
+                .add_exit_system(GameState::Playing, stop_with_fade::<T>);
+        }
+        self
+    }
+}
+
+pub struct Mixer {
+    levels: MixerSettings,
+    // 1 is full volume, goes down to DUCK_LEVEL
+    duck: f32,
+    // a voice channel was playing last frame
+    talking: bool,
+    // per channel level on top of the bus, for fading layers in and out
+    faders: HashMap<TypeId, f32>,
+}
+
+impl Default for Mixer {
+    fn default() -> Self {
+        Self {
+            levels: MixerSettings::default(),
+            duck: 1.0,
+            talking: false,
+            faders: HashMap::default(),
+        }
+    }
+}
+
+impl Mixer {
+    pub fn bus_volume(&self, bus: Bus) -> f64 {
+        let duck = if bus == Bus::Music { self.duck } else { 1.0 };
+        (self.levels.master * self.levels.level(bus) * bus.gain() * duck) as f64
+    }
+
+    // use for with_volume when playing on a channel
+    pub fn volume<T: MixerChannel>(&self) -> f64 {
+        self.bus_volume(T::BUS) * self.fader::<T>() as f64
+    }
+
+    pub fn fader<T: MixerChannel>(&self) -> f32 {
+        self.faders.get(&TypeId::of::<T>()).copied().unwrap_or(1.0)
+    }
+
+    pub fn set_fader<T: MixerChannel>(&mut self, level: f32) {
+        self.faders.insert(TypeId::of::<T>(), level);
+    }
+}
+
+pub fn fade(seconds: f32) -> AudioTween {
+    AudioTween::new(Duration::from_secs_f32(seconds), AudioEasing::Linear)
+}
+
+// exit system, pausing keeps the position so music can pick up where it left off
+pub fn pause_with_fade<T: Resource>(channel: Res<AudioChannel<T>>) {
+    channel.pause().fade_out(fade(1.0));
+}
+
+pub fn resume_with_fade<T: Resource>(channel: Res<AudioChannel<T>>) {
+    channel.resume().fade_in(fade(0.5));
+}
+
+pub fn stop_with_fade<T: Resource>(channel: Res<AudioChannel<T>>) {
+    channel.stop().fade_out(fade(1.0));
+}
+
+fn update_mixer(mut mixer: ResMut<Mixer>, settings: Res<Settings>, time: Res<Time>) {
+    if settings.is_changed() && mixer.levels != settings.audio {
+        mixer.levels = settings.audio.clone();
+    }
+
+    let target = if mixer.talking { DUCK_LEVEL } else { 1.0 };
+    let step = DUCK_SPEED * time.delta_seconds();
+    let duck = if mixer.duck < target {
+        (mixer.duck + step).min(target)
+    } else {
+        (mixer.duck - step).max(target)
+    };
+    mixer.duck = duck;
+    mixer.talking = false;
+}
+
+fn apply_volume<T: MixerChannel>(
+    mut mixer: ResMut<Mixer>,
+    channel: Res<AudioChannel<T>>,
+    // only on channels added with add_spatial_channel
+    emitter: Option<Res<ChannelEmitter<T>>>,
+    mut applied: Local<Option<(f64, f64, u32)>>,
+) {
+    if T::BUS == Bus::Voice && channel.is_playing_sound() {
+        mixer.talking = true;
+    }
+
+    let (volume, panning, plays) = match &emitter {
+        Some(emitter) => (
+            mixer.volume::<T>() * emitter.volume() as f64,
+            emitter.panning(),
+            emitter.plays(),
+        ),
+        None => (mixer.volume::<T>(), 0.5, 0),
+    };
+    if *applied != Some((volume, panning, plays)) {
+        channel.set_volume(volume);
+        channel.set_panning(panning);
+        *applied = Some((volume, panning, plays));
+    }
+}
@@ -0,0 +1,350 @@
+// music for the reactor round, stems all play in sync and fade in and out with how intense things are
+// tracks live in assets/music, changes wait for the next beat or bar so they land in time
+use std::collections::HashMap;
+
+use bevy::{
+    asset::{AssetLoader, AssetPath, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use bevy_kira_audio::prelude::*;
+use bevy_kira_audio::{AudioSource, InstanceHandle, PlaybackState};
+use iyes_loopless::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    assets::AudioAssets,
+    mixer::{Bus, Mixer, MixerApp, MixerChannel},
+    prefabs::RoundEvent,
+    states::{GameTimer, PauseState},
+    GameState,
+};
+
+// one channel per stem, kira can't fade single sounds in this version
+const MAX_STEMS: usize = 4;
+// same as the timer text going red
+const FINAL_SECONDS: f32 = 10.0;
+// how long meltdown and victory play after a round before settling back to idle
+const AFTER_ROUND: f32 = 8.0;
+
+pub struct MusicPlugin;
+
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<MusicTrack>()
+            .init_asset_loader::<MusicLoader>()
+            .init_resource::<Music>()
+            .add_system(
+                update_music
+                    .run_in_state(GameState::Playing)
+                    .run_in_state(PauseState::Running),
+            )
+            .add_exit_system(GameState::Playing, reset_music);
+
+        add_stem::<0>(app);
+        add_stem::<1>(app);
+        add_stem::<2>(app);
+        add_stem::<3>(app);
+    }
+}
+
+fn add_stem<const N: usize>(app: &mut App) {
+    app.add_mixer_channel::<MusicStem<N>>().add_system(
+        update_stem::<N>
+            .run_in_state(GameState::Playing)
+            .run_in_state(PauseState::Running)
+            .after(update_music),
+    );
+}
+
+pub struct MusicStem<const N: usize>;
+
+impl<const N: usize> MixerChannel for MusicStem<N> {
+    const BUS: Bus = Bus::Music;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum Intensity {
+    Idle,
+    Round,
+    // last few seconds of the round
+    Final,
+    // round ended with nothing stabilized
+    Meltdown,
+    Victory,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum Quantize {
+    Beat,
+    Bar,
+}
+
+#[derive(Deserialize, TypeUuid)]
+#[uuid = "8e3b1f4a-6c2d-4e5f-9a7b-0c1d2e3f4a5b"]
+pub struct MusicTrack {
+    // without a tempo there's nothing to line up with, changes happen straight away
+    #[serde(default)]
+    pub bpm: Option<f32>,
+    #[serde(default = "four")]
+    pub beats_per_bar: u32,
+    // seconds for a stem to fade all the way in or out
+    #[serde(default = "one")]
+    pub fade: f32,
+    // when each intensity is allowed to start, anything missing waits for a bar
+    #[serde(default)]
+    pub quantize: HashMap<Intensity, Quantize>,
+    pub stems: Vec<MusicStemConfig>,
+}
+
+#[derive(Deserialize)]
+pub struct MusicStemConfig {
+    pub audio: String,
+    // 0-1 per intensity, silent for anything missing
+    #[serde(default)]
+    pub levels: HashMap<Intensity, f32>,
+}
+
+fn four() -> u32 {
+    4
+}
+
+fn one() -> f32 {
+    1.0
+}
+
+impl MusicTrack {
+    pub fn audio(&self) -> impl Iterator<Item = &str> {
+        self.stems.iter().map(|stem| stem.audio.as_str())
+    }
+
+    fn seconds(&self, quantize: Quantize) -> f64 {
+        let beat = 60.0 / self.bpm.unwrap_or_default().max(1.0) as f64;
+        match quantize {
+            Quantize::Beat => beat,
+            Quantize::Bar => beat * self.beats_per_bar.max(1) as f64,
+        }
+    }
+
+    fn level(&self, stem: usize, intensity: Intensity) -> f32 {
+        self.stems
+            .get(stem)
+            .and_then(|stem| stem.levels.get(&intensity))
+            .copied()
+            .unwrap_or(0.0)
+    }
+}
+
+#[derive(Default)]
+pub struct MusicLoader;
+
+impl AssetLoader for MusicLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let track = ron::de::from_bytes::<MusicTrack>(bytes)?;
+            if track.stems.len() > MAX_STEMS {
+                warn!(
+                    "{:?} has {} stems, only the first {} will play",
+                    load_context.path(),
+                    track.stems.len(),
+                    MAX_STEMS
+                );
+            }
+
+            let audio = track
+                .audio()
+                .map(|path| AssetPath::from(path).to_owned())
+                .collect::<Vec<_>>();
+
+            load_context.set_default_asset(LoadedAsset::new(track).with_dependencies(audio));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["music.ron"]
+    }
+}
+
+pub struct Music {
+    pub intensity: Intensity,
+    // waiting for the next beat or bar
+    pending: Option<Intensity>,
+    // meltdown or victory, and when the round ended
+    after_round: Option<(Intensity, f64)>,
+    playing: bool,
+    // stems start together on the frame this is set
+    cue: bool,
+    instances: [Option<InstanceHandle>; MAX_STEMS],
+    levels: [f32; MAX_STEMS],
+    // seconds into the loop, read from the first stem
+    position: Option<f64>,
+    last_beat: i64,
+    last_bar: i64,
+}
+
+impl Default for Music {
+    fn default() -> Self {
+        Self {
+            intensity: Intensity::Idle,
+            pending: None,
+            after_round: None,
+            playing: false,
+            cue: false,
+            instances: Default::default(),
+            levels: [0.0; MAX_STEMS],
+            position: None,
+            last_beat: -1,
+            last_bar: -1,
+        }
+    }
+}
+
+fn update_music(
+    mut music: ResMut<Music>,
+    mut round_events: EventReader<RoundEvent>,
+    audio_assets: Res<AudioAssets>,
+    tracks: Res<Assets<MusicTrack>>,
+    asset_server: Res<AssetServer>,
+    audio_sources: Res<Assets<AudioSource>>,
+    game_timer: Res<GameTimer>,
+    time: Res<Time>,
+) {
+    let now = time.seconds_since_startup();
+    music.cue = false;
+
+    for event in round_events.iter() {
+        music.after_round = match event {
+            RoundEvent::Started => None,
+            RoundEvent::Finished { score: 0 } => Some((Intensity::Meltdown, now)),
+            RoundEvent::Finished { .. } => Some((Intensity::Victory, now)),
+        };
+    }
+
+    let track = match tracks.get(&audio_assets.music) {
+        Some(track) => track,
+        None => return,
+    };
+
+    let target = match music.after_round {
+        Some((intensity, started)) if now - started < AFTER_ROUND as f64 => intensity,
+        _ => {
+            let timer = &game_timer.0;
+            let remaining = timer.duration().as_secs_f32() - timer.elapsed().as_secs_f32();
+            if timer.duration().is_zero() || timer.finished() {
+                Intensity::Idle
+            } else if remaining <= FINAL_SECONDS {
+                Intensity::Final
+            } else {
+                Intensity::Round
+            }
+        }
+    };
+
+    if !music.playing {
+        // wait for every stem, starting late would put them out of sync
+        let loaded = track.stems.iter().all(|stem| {
+            let handle = asset_server.get_handle::<AudioSource, _>(stem.audio.as_str());
+            audio_sources.contains(&handle)
+        });
+        if loaded {
+            music.playing = true;
+            music.cue = true;
+            music.intensity = target;
+            for (i, level) in music.levels.iter_mut().enumerate() {
+                *level = track.level(i, target);
+            }
+        }
+        return;
+    }
+
+    if target != music.intensity {
+        music.pending = Some(target);
+    } else {
+        music.pending = None;
+    }
+
+    if track.bpm.is_none() {
+        if let Some(pending) = music.pending.take() {
+            music.intensity = pending;
+        }
+    } else if let Some(position) = music.position {
+        let beat = (position / track.seconds(Quantize::Beat)) as i64;
+        let bar = (position / track.seconds(Quantize::Bar)) as i64;
+        // looping back to the start counts too
+        let on_beat = beat != music.last_beat;
+        let on_bar = bar != music.last_bar;
+        music.last_beat = beat;
+        music.last_bar = bar;
+
+        if let Some(pending) = music.pending {
+            let quantize = track
+                .quantize
+                .get(&pending)
+                .copied()
+                .unwrap_or(Quantize::Bar);
+            let ready = match quantize {
+                Quantize::Beat => on_beat,
+                Quantize::Bar => on_bar,
+            };
+            if ready {
+                music.intensity = pending;
+                music.pending = None;
+            }
+        }
+    }
+
+    let step = time.delta_seconds() / track.fade.max(0.01);
+    let intensity = music.intensity;
+    for (i, level) in music.levels.iter_mut().enumerate() {
+        let target = track.level(i, intensity);
+        *level = if *level < target {
+            (*level + step).min(target)
+        } else {
+            (*level - step).max(target)
+        };
+    }
+}
+
+fn update_stem<const N: usize>(
+    mut music: ResMut<Music>,
+    mut mixer: ResMut<Mixer>,
+    audio_assets: Res<AudioAssets>,
+    tracks: Res<Assets<MusicTrack>>,
+    asset_server: Res<AssetServer>,
+    channel: Res<AudioChannel<MusicStem<N>>>,
+) {
+    mixer.set_fader::<MusicStem<N>>(music.levels[N]);
+
+    if music.cue {
+        let stem = tracks
+            .get(&audio_assets.music)
+            .and_then(|track| track.stems.get(N));
+        if let Some(stem) = stem {
+            let handle = asset_server.get_handle::<AudioSource, _>(stem.audio.as_str());
+            let instance = channel
+                .play(handle)
+                .looped()
+                .with_volume(mixer.volume::<MusicStem<N>>())
+                .handle();
+            music.instances[N] = Some(instance);
+        }
+    }
+
+    // the first stem keeps time for the others
+    if N == 0 {
+        music.position = match music.instances[N].clone().map(|i| channel.state(i)) {
+            Some(PlaybackState::Playing { position }) => Some(position),
+            _ => None,
+        };
+    }
+}
+
+fn reset_music(mut commands: Commands) {
+    commands.insert_resource(Music::default());
+}
@@ -0,0 +1,313 @@
+// short reactive lines, gameplay sends a Bark and the ai decides if it's worth saying
+// pools live in assets/dialogue/nexus.barks.ron
+use std::collections::HashMap;
+
+use bevy::{
+    asset::{AssetLoader, AssetPath, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use bevy_kira_audio::prelude::*;
+use bevy_kira_audio::AudioSource;
+use rand::{distributions::WeightedIndex, prelude::*};
+use serde::Deserialize;
+
+use crate::{
+    assets::DialogueAssets,
+    locale::Strings,
+    mixer::Mixer,
+    prefabs::{Door, PelletEvent, RespawnEvent, RespawnReason},
+    spatial_audio::ChannelEmitter,
+    states::GameTimer,
+    subtitles::PlayCaption,
+};
+
+use super::{AIAudioChannel, DialogueRunner, AI};
+
+// pellets balanced in a row before the ai comments on it
+const STREAK_LENGTH: u32 = 5;
+const TIME_WARNING: f32 = 10.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum Bark {
+    Poked,
+    Fell,
+    Hazard,
+    RoundStart,
+    HighScore,
+    PelletLost,
+    Streak,
+    TimeRunningOut,
+    DoorOpened,
+}
+
+pub struct BarkEvent(pub Bark);
+
+#[derive(Deserialize, TypeUuid)]
+#[uuid = "2c7a9e4b-3d5f-4a1b-8c6d-7e8f9a0b1c2d"]
+pub struct BarkSet {
+    // seconds of quiet after any bark, keeps the ai from chaining lines
+    #[serde(default)]
+    pub min_gap: f32,
+    pub pools: HashMap<Bark, BarkPool>,
+}
+
+impl BarkSet {
+    // every voice line in every pool
+    pub fn audio(&self) -> impl Iterator<Item = &str> {
+        self.pools
+            .values()
+            .flat_map(|pool| pool.lines.iter())
+            .filter_map(|line| line.audio.as_deref())
+    }
+}
+
+#[derive(Deserialize)]
+pub struct BarkPool {
+    // higher wins when several barks happen at once
+    #[serde(default)]
+    pub priority: u32,
+    // seconds before this pool can be used again
+    #[serde(default)]
+    pub cooldown: f32,
+    // odds of saying anything at all, so common events don't always get a line
+    #[serde(default = "one")]
+    pub chance: f32,
+    // may cut off a lower priority bark that's still playing
+    #[serde(default)]
+    pub interrupt: bool,
+    // seconds the bark can wait for the ai to be free, 0 drops it straight away
+    #[serde(default)]
+    pub queue: f32,
+    pub lines: Vec<BarkLine>,
+}
+
+#[derive(Deserialize)]
+pub struct BarkLine {
+    #[serde(default)]
+    pub audio: Option<String>,
+    // string table key, shown when the audio has no captions, or on its own when there is no audio
+    #[serde(default)]
+    pub subtitle: String,
+    #[serde(default = "one")]
+    pub weight: f32,
+}
+
+fn one() -> f32 {
+    1.0
+}
+
+#[derive(Default)]
+pub struct BarkLoader;
+
+impl AssetLoader for BarkLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let set = ron::de::from_bytes::<BarkSet>(bytes)?;
+
+            let audio = set
+                .audio()
+                .map(|path| AssetPath::from(path).to_owned())
+                .collect::<Vec<_>>();
+
+            load_context.set_default_asset(LoadedAsset::new(set).with_dependencies(audio));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["barks.ron"]
+    }
+}
+
+#[derive(Default)]
+pub struct Barks {
+    // time each pool was last used
+    used: HashMap<Bark, f64>,
+    // last line per pool, so the same line doesn't play twice in a row
+    last_line: HashMap<Bark, usize>,
+    // priority and end time of the bark playing now
+    current: Option<(u32, f64)>,
+    last_end: f64,
+    // barks waiting for the ai and when they give up
+    queued: Vec<(Bark, f64)>,
+    streak: u32,
+}
+
+// turn gameplay events into barks
+pub fn listen_for_barks(
+    mut bark_events: EventWriter<BarkEvent>,
+    mut respawn_events: EventReader<RespawnEvent>,
+    mut pellet_events: EventReader<PelletEvent>,
+    door_query: Query<(&Door, ChangeTrackers<Door>)>,
+    game_timer: Res<GameTimer>,
+    mut barks: ResMut<Barks>,
+    mut last_remaining: Local<f32>,
+) {
+    for RespawnEvent(reason) in respawn_events.iter() {
+        bark_events.send(BarkEvent(match reason {
+            RespawnReason::Fell => Bark::Fell,
+            RespawnReason::Hazard => Bark::Hazard,
+        }));
+    }
+
+    for event in pellet_events.iter() {
+        match event {
+            PelletEvent::Stabilized => {
+                barks.streak += 1;
+                if barks.streak % STREAK_LENGTH == 0 {
+                    bark_events.send(BarkEvent(Bark::Streak));
+                }
+            }
+            PelletEvent::Lost => {
+                barks.streak = 0;
+                bark_events.send(BarkEvent(Bark::PelletLost));
+            }
+        }
+    }
+
+    for (door, tracker) in door_query.iter() {
+        if tracker.is_changed() && !tracker.is_added() && *door == Door::Open {
+            bark_events.send(BarkEvent(Bark::DoorOpened));
+        }
+    }
+
+    let timer = &game_timer.0;
+    let remaining = timer.duration().as_secs_f32() - timer.elapsed().as_secs_f32();
+    // timer was reset, a new round started
+    if remaining > *last_remaining {
+        barks.streak = 0;
+    }
+    if *last_remaining > TIME_WARNING && remaining <= TIME_WARNING && !timer.finished() {
+        bark_events.send(BarkEvent(Bark::TimeRunningOut));
+    }
+    *last_remaining = remaining;
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn play_barks(
+    mut bark_events: EventReader<BarkEvent>,
+    mut barks: ResMut<Barks>,
+    mut caption_events: EventWriter<PlayCaption>,
+    ai_query: Query<(Entity, &AI, Option<&Name>)>,
+    runner_query: Query<(), With<DialogueRunner>>,
+    dialogue_assets: Res<DialogueAssets>,
+    bark_sets: Res<Assets<BarkSet>>,
+    strings: Res<Strings>,
+    asset_server: Res<AssetServer>,
+    audio_sources: Res<Assets<AudioSource>>,
+    channel: Res<AudioChannel<AIAudioChannel>>,
+    mut emitter: ResMut<ChannelEmitter<AIAudioChannel>>,
+    mixer: Res<Mixer>,
+    time: Res<Time>,
+) {
+    let now = time.seconds_since_startup();
+    let set = match bark_sets.get(&dialogue_assets.barks) {
+        Some(set) => set,
+        None => return,
+    };
+
+    // barks are reactions, anything we can't say right now is dropped unless its pool queues it
+    let mut rng = thread_rng();
+    barks.queued.retain(|(_, until)| *until > now);
+    let queued = barks
+        .queued
+        .iter()
+        .filter_map(|(bark, _)| set.pools.get(bark).map(|pool| (*bark, pool)))
+        .collect::<Vec<_>>();
+    let bark = bark_events
+        .iter()
+        .filter_map(|BarkEvent(bark)| set.pools.get(bark).map(|pool| (*bark, pool)))
+        .filter(|(bark, pool)| {
+            barks
+                .used
+                .get(bark)
+                .map_or(true, |used| now - used >= pool.cooldown as f64)
+        })
+        .filter(|(_, pool)| rng.gen::<f32>() < pool.chance)
+        .chain(queued)
+        .max_by_key(|(_, pool)| pool.priority);
+    let (bark, pool) = match bark {
+        Some(bark) => bark,
+        None => return,
+    };
+
+    let playing = barks.current.filter(|(_, end)| *end > now);
+    let busy = playing.is_some() || channel.is_playing_sound();
+    let free = if busy {
+        // only barks can be interrupted, never something else the ai is saying
+        matches!(playing, Some((priority, _)) if pool.interrupt && pool.priority > priority)
+    } else {
+        now - barks.last_end >= set.min_gap as f64
+    };
+
+    // the intro and other scripts own the ai until they're done
+    let (ai_entity, speaker) = match ai_query.iter().find(|(_, ai, _)| **ai == AI::Idle) {
+        Some((e, _, name)) if free && runner_query.is_empty() => {
+            (e, name.map(|n| n.as_str().to_string()))
+        }
+        _ => {
+            if pool.queue > 0.0 && !barks.queued.iter().any(|(queued, _)| *queued == bark) {
+                barks.queued.push((bark, now + pool.queue as f64));
+            }
+            return;
+        }
+    };
+    if busy {
+        channel.stop();
+    }
+
+    let last = barks.last_line.get(&bark).copied();
+    let weights = pool.lines.iter().enumerate().map(|(i, line)| {
+        if pool.lines.len() > 1 && Some(i) == last {
+            0.0
+        } else {
+            line.weight
+        }
+    });
+    let index = match WeightedIndex::new(weights) {
+        Ok(dist) => dist.sample(&mut rng),
+        Err(_) => return,
+    };
+    let line = &pool.lines[index];
+    let subtitle = strings.get(&line.subtitle);
+
+    let duration = match &line.audio {
+        Some(path) => {
+            let handle = asset_server.load::<AudioSource, _>(path.as_str());
+            let source = match audio_sources.get(&handle) {
+                Some(source) => source,
+                None => return,
+            };
+            emitter.play_from(ai_entity);
+            let instance = channel
+                .play(handle.clone())
+                .with_volume(mixer.volume::<AIAudioChannel>())
+                .handle();
+            caption_events.send(
+                PlayCaption::new::<AIAudioChannel>(handle, instance)
+                    .with_fallback(speaker, subtitle),
+            );
+            source.sound.duration().as_secs_f64()
+        }
+        None => {
+            // roughly reading speed
+            let duration = 1.5 + subtitle.chars().count() as f32 * 0.05;
+            caption_events.send(PlayCaption::text::<AIAudioChannel>(
+                speaker, subtitle, duration,
+            ));
+            duration as f64
+        }
+    };
+
+    barks.queued.retain(|(queued, _)| *queued != bark);
+    barks.used.insert(bark, now);
+    barks.last_line.insert(bark, index);
+    barks.current = Some((pool.priority, now + duration));
+    barks.last_end = now + duration;
+}
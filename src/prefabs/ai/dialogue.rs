@@ -0,0 +1,375 @@
+// scripted ai dialogue, loaded from assets/dialogue/*.dialogue.ron so lines and tutorial
+// steps can be changed without touching rust
+use std::time::Duration;
+
+use bevy::{
+    asset::{AssetLoader, AssetPath, LoadContext, LoadState, LoadedAsset},
+    ecs::system::SystemParam,
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use bevy_kira_audio::prelude::*;
+use bevy_kira_audio::AudioSource;
+use iyes_loopless::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    actions::{Action, ActionState},
+    camera::CameraPlayerConfig,
+    locale::Strings,
+    mixer::Mixer,
+    prefabs::{PolarityBlaster, Switch, SwitchEvent, SwitchState},
+    save::SaveSlots,
+    spatial_audio::ChannelEmitter,
+    states::Score,
+    subtitles::PlayCaption,
+    LevelState,
+};
+
+use super::{AIAudioChannel, AIBehaviour, AIMovement, AI};
+
+#[derive(Deserialize, TypeUuid)]
+#[uuid = "5d4c2f6e-8a1b-4c3d-9e7f-1a2b3c4d5e6f"]
+pub struct DialogueScript {
+    pub steps: Vec<DialogueStep>,
+}
+
+// Steps run in order, a step has to finish before the next one starts
+#[derive(Deserialize)]
+pub enum DialogueStep {
+    // play a voice line, then wait delay seconds
+    Line {
+        #[serde(default)]
+        audio: Option<String>,
+        // string table key
+        #[serde(default)]
+        subtitle: String,
+        #[serde(default)]
+        delay: f32,
+    },
+    // hold until something happens
+    Wait(DialogueWait),
+    // change something in the level, takes no time
+    Do(DialogueAction),
+}
+
+impl DialogueScript {
+    // every voice line the script plays
+    pub fn audio(&self) -> impl Iterator<Item = &str> {
+        self.steps.iter().filter_map(|step| match step {
+            DialogueStep::Line {
+                audio: Some(path), ..
+            } => Some(path.as_str()),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+pub enum DialogueWait {
+    Seconds(f32),
+    // any look input, mouse or stick
+    Looked,
+    Moved,
+    // entity with this name ends up in the player's hands
+    PickedUp(String),
+    SwitchFlipped,
+    ScoreReached(u32),
+}
+
+#[derive(Deserialize)]
+pub enum DialogueAction {
+    EnableLook(bool),
+    EnableMovement(bool),
+    // by name, works on blasters and switches
+    Enable(String),
+    Disable(String),
+    SetSwitch(String, SwitchState),
+    SetLevel(LevelState),
+    // saved, later visits to the level skip straight past the tutorial
+    CompleteTutorial,
+    // the speaker is done with the script and goes back to idle chatter
+    Idle,
+    // where the speaker floats, by name for MoveTo and Lead
+    MoveTo(String),
+    Lead(String),
+    Follow,
+    Stay,
+}
+
+#[derive(Default)]
+pub struct DialogueLoader;
+
+impl AssetLoader for DialogueLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let script = ron::de::from_bytes::<DialogueScript>(bytes)?;
+
+            // load the voice lines along with the script
+            let audio = script
+                .audio()
+                .map(|path| AssetPath::from(path).to_owned())
+                .collect::<Vec<_>>();
+
+            load_context.set_default_asset(LoadedAsset::new(script).with_dependencies(audio));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["dialogue.ron"]
+    }
+}
+
+// Plays a script, the entity it's on is the speaker
+#[derive(Component)]
+pub struct DialogueRunner {
+    pub script: Handle<DialogueScript>,
+    step: usize,
+    // running line or timed wait
+    timer: Option<Timer>,
+    // skip the whole script as soon as it's loaded, like pressing skip on the first line
+    skip: bool,
+}
+
+impl DialogueRunner {
+    pub fn new(script: Handle<DialogueScript>) -> Self {
+        Self {
+            script,
+            step: 0,
+            timer: None,
+            skip: false,
+        }
+    }
+
+    pub fn with_skip(mut self, skip: bool) -> Self {
+        self.skip = skip;
+        self
+    }
+}
+
+// everything a script is allowed to change
+#[derive(SystemParam)]
+pub struct DialogueTargets<'w, 's> {
+    commands: Commands<'w, 's>,
+    camera_config: ResMut<'w, CameraPlayerConfig>,
+    name_query: Query<'w, 's, (Entity, &'static Name)>,
+    parent_query: Query<'w, 's, (), With<Parent>>,
+    blaster_query: Query<'w, 's, &'static mut PolarityBlaster>,
+    switch_query: Query<'w, 's, &'static mut Switch>,
+    ai_query: Query<'w, 's, &'static mut AI>,
+    movement_query: Query<'w, 's, &'static mut AIMovement>,
+    saves: ResMut<'w, SaveSlots>,
+}
+
+impl<'w, 's> DialogueTargets<'w, 's> {
+    fn find(&self, name: &str) -> Vec<Entity> {
+        let found = self
+            .name_query
+            .iter()
+            .filter(|(_, n)| n.as_str() == name)
+            .map(|(e, _)| e)
+            .collect::<Vec<_>>();
+        if found.is_empty() {
+            warn!("dialogue: nothing named {}", name);
+        }
+        found
+    }
+
+    fn set_enabled(&mut self, name: &str, enabled: bool) {
+        for e in self.find(name) {
+            if let Ok(mut blaster) = self.blaster_query.get_mut(e) {
+                *blaster = if enabled {
+                    PolarityBlaster::Enabled
+                } else {
+                    PolarityBlaster::Disabled
+                };
+            }
+            if let Ok(mut switch) = self.switch_query.get_mut(e) {
+                switch.state = if enabled {
+                    SwitchState::Enabled
+                } else {
+                    SwitchState::Disabled
+                };
+            }
+        }
+    }
+
+    fn apply(&mut self, action: &DialogueAction, speaker: Entity) {
+        match action {
+            DialogueAction::EnableLook(enabled) => self.camera_config.disable_look = !enabled,
+            DialogueAction::EnableMovement(enabled) => {
+                self.camera_config.disable_movement = !enabled
+            }
+            DialogueAction::Enable(name) => self.set_enabled(name, true),
+            DialogueAction::Disable(name) => self.set_enabled(name, false),
+            DialogueAction::SetSwitch(name, state) => {
+                for e in self.find(name) {
+                    if let Ok(mut switch) = self.switch_query.get_mut(e) {
+                        switch.state = *state;
+                    }
+                }
+            }
+            DialogueAction::SetLevel(level) => {
+                self.commands.insert_resource(NextState(level.clone()))
+            }
+            DialogueAction::CompleteTutorial => {
+                self.saves.current_mut().tutorial_complete = true;
+                self.saves.save();
+            }
+            DialogueAction::Idle => {
+                if let Ok(mut ai) = self.ai_query.get_mut(speaker) {
+                    *ai = AI::Idle;
+                }
+            }
+            DialogueAction::MoveTo(name) => {
+                if let Some(&target) = self.find(name).first() {
+                    self.set_behaviour(speaker, AIBehaviour::GoTo(target));
+                }
+            }
+            DialogueAction::Lead(name) => {
+                if let Some(&target) = self.find(name).first() {
+                    self.set_behaviour(speaker, AIBehaviour::Lead(target));
+                }
+            }
+            DialogueAction::Follow => self.set_behaviour(speaker, AIBehaviour::Follow),
+            DialogueAction::Stay => {
+                if let Ok(mut movement) = self.movement_query.get_mut(speaker) {
+                    movement.behaviour = AIBehaviour::Stay(movement.position());
+                }
+            }
+        }
+    }
+
+    fn set_behaviour(&mut self, speaker: Entity, behaviour: AIBehaviour) {
+        if let Ok(mut movement) = self.movement_query.get_mut(speaker) {
+            movement.behaviour = behaviour;
+        }
+    }
+
+    fn picked_up(&self, name: &str) -> bool {
+        self.name_query
+            .iter()
+            .filter(|(_, n)| n.as_str() == name)
+            .any(|(e, _)| self.parent_query.contains(e))
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn run_dialogue(
+    mut runner_query: Query<(Entity, &mut DialogueRunner, Option<&Name>)>,
+    mut targets: DialogueTargets,
+    mut switch_events: EventReader<SwitchEvent>,
+    mut caption_events: EventWriter<PlayCaption>,
+    scripts: Res<Assets<DialogueScript>>,
+    strings: Res<Strings>,
+    asset_server: Res<AssetServer>,
+    audio_sources: Res<Assets<AudioSource>>,
+    channel: Res<AudioChannel<AIAudioChannel>>,
+    mut emitter: ResMut<ChannelEmitter<AIAudioChannel>>,
+    mixer: Res<Mixer>,
+    actions: Res<ActionState>,
+    score: Res<Score>,
+    time: Res<Time>,
+) {
+    let flipped = switch_events.iter().count() > 0;
+
+    for (e, mut runner, name) in runner_query.iter_mut() {
+        let script = match scripts.get(&runner.script) {
+            Some(script) => script,
+            None => continue,
+        };
+
+        // skip the talking, but still do everything the script would have done
+        if runner.skip || actions.just_pressed(Action::SkipDialog) {
+            runner.skip = false;
+            channel.stop();
+            for step in script.steps.iter().skip(runner.step) {
+                if let DialogueStep::Do(action) = step {
+                    targets.apply(action, e);
+                }
+            }
+            runner.step = script.steps.len();
+        }
+
+        while let Some(step) = script.steps.get(runner.step) {
+            let done = match step {
+                DialogueStep::Line {
+                    audio,
+                    subtitle,
+                    delay,
+                } => match runner.timer.as_mut() {
+                    Some(timer) => timer.tick(time.delta()).finished(),
+                    None => {
+                        let speaker = name.map(|n| n.as_str().to_string());
+                        let subtitle = strings.get(subtitle);
+                        let mut duration = Duration::from_secs_f32(*delay);
+                        match audio {
+                            Some(path) => {
+                                let handle = asset_server.load::<AudioSource, _>(path.as_str());
+                                match audio_sources.get(&handle) {
+                                    Some(source) => {
+                                        duration += source.sound.duration();
+                                        emitter.play_from(e);
+                                        let instance = channel
+                                            .play(handle.clone())
+                                            .with_volume(mixer.volume::<AIAudioChannel>())
+                                            .handle();
+                                        caption_events.send(
+                                            PlayCaption::new::<AIAudioChannel>(handle, instance)
+                                                .with_fallback(speaker, subtitle),
+                                        );
+                                    }
+                                    // still loading, try again next frame
+                                    None if asset_server.get_load_state(&handle)
+                                        != LoadState::Failed =>
+                                    {
+                                        break
+                                    }
+                                    None => warn!("dialogue: failed to load {}", path),
+                                }
+                            }
+                            // no voice, just text on screen
+                            None => caption_events.send(PlayCaption::text::<AIAudioChannel>(
+                                speaker, subtitle, *delay,
+                            )),
+                        }
+                        runner.timer = Some(Timer::new(duration, false));
+                        false
+                    }
+                },
+                DialogueStep::Wait(wait) => match wait {
+                    DialogueWait::Seconds(seconds) => runner
+                        .timer
+                        .get_or_insert_with(|| Timer::from_seconds(*seconds, false))
+                        .tick(time.delta())
+                        .finished(),
+                    DialogueWait::Looked => actions.look() != Vec2::ZERO,
+                    DialogueWait::Moved => actions.movement() != Vec2::ZERO,
+                    DialogueWait::PickedUp(name) => targets.picked_up(name),
+                    DialogueWait::SwitchFlipped => flipped,
+                    DialogueWait::ScoreReached(target) => score.0 >= *target,
+                },
+                DialogueStep::Do(action) => {
+                    targets.apply(action, e);
+                    true
+                }
+            };
+
+            if !done {
+                break;
+            }
+            runner.timer = None;
+            runner.step += 1;
+        }
+
+        if runner.step >= script.steps.len() {
+            targets.commands.entity(e).remove::<DialogueRunner>();
+        }
+    }
+}
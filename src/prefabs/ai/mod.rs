@@ -1,10 +1,15 @@
 mod audio;
+mod barks;
+mod dialogue;
+mod movement;
 use std::time::Duration;
 
 pub use audio::*;
-use bevy_kira_audio::AudioSource;
+pub use barks::*;
+pub use dialogue::*;
+pub use movement::*;
 
-use bevy::{input::mouse::MouseMotion, math::vec3, prelude::*};
+use bevy::{math::vec3, prelude::*};
 use bevy_inspector_egui::{Inspectable, RegisterInspectable};
 use bevy_kira_audio::prelude::*;
 use bevy_tweening::{lens::*, *};
@@ -12,25 +17,60 @@ use iyes_loopless::prelude::*;
 use sly_physics::prelude::*;
 
 use crate::{
-    camera::CameraPlayerConfig,
-    cursor::{CursorInteraction, InteractionTime},
+    camera::CameraState,
+    cursor::{CursorInteraction, Interactable},
+    labels::WorldLabel,
+    mixer::MixerApp,
+    spatial_audio::{AudioEmitter, SpatialAudioApp},
+    states::PauseState,
+    subtitles::{CaptionApp, Captions},
     GameState,
 };
 
-use super::{PolarityBlaster, Switch, SwitchState};
-
 pub struct AIPlugin;
 
 impl Plugin for AIPlugin {
     fn build(&self, app: &mut App) {
-        app.add_audio_channel::<AIAudioChannel>()
-            .add_event::<AIAnnoyEvent>()
-            .add_enter_system(GameState::Playing, setup_annoy_config)
-            .add_enter_system(GameState::Playing, setup_intro_config)
-            .add_enter_system(GameState::Playing, setup_high_config)
-            .add_system(advance_intro.run_in_state(GameState::Playing))
+        app.add_mixer_channel::<AIAudioChannel>()
+            .add_spatial_channel::<AIAudioChannel>()
+            .add_caption_channel::<AIAudioChannel>()
+            .add_asset::<DialogueScript>()
+            .init_asset_loader::<DialogueLoader>()
+            .add_asset::<BarkSet>()
+            .init_asset_loader::<BarkLoader>()
+            .init_resource::<Barks>()
+            .add_event::<BarkEvent>()
+            .add_system(
+                run_dialogue
+                    .run_in_state(GameState::Playing)
+                    .run_in_state(PauseState::Running)
+                    .run_not_in_state(CameraState::Cinematic),
+            )
+            .init_resource::<AIMovementConfig>()
             .add_system(spawn_ai.run_in_state(GameState::Playing))
-            .add_system(interaction_check.run_in_state(GameState::Playing));
+            .add_system(
+                move_ai
+                    .run_in_state(GameState::Playing)
+                    .run_in_state(PauseState::Running),
+            )
+            .add_system(update_interactable.run_in_state(GameState::Playing))
+            .add_system(
+                interaction_check
+                    .run_in_state(GameState::Playing)
+                    .run_in_state(PauseState::Running),
+            )
+            .add_system(
+                listen_for_barks
+                    .run_in_state(GameState::Playing)
+                    .run_in_state(PauseState::Running),
+            )
+            .add_system(
+                play_barks
+                    .run_in_state(GameState::Playing)
+                    .run_in_state(PauseState::Running)
+                    .after(listen_for_barks),
+            )
+            .add_system(update_speech_bubble.run_in_state(GameState::Playing));
 
         app.register_inspectable::<AI>();
     }
@@ -48,9 +88,9 @@ pub fn spawn_ai(
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut collider_resources: ResMut<ColliderResources>,
-    query: Query<(Entity, &AI, &Transform), Added<AI>>,
+    query: Query<(Entity, &Transform), Added<AI>>,
 ) {
-    for (e, ai, trans) in query.iter() {
+    for (e, trans) in query.iter() {
         let child_mat = materials.add(StandardMaterial {
             base_color: Color::rgba(0.9, 0.9, 0.9, 0.5),
             unlit: true,
@@ -71,8 +111,12 @@ pub fn spawn_ai(
                 mass: Mass(2.0),
                 ..default()
             })
-            .insert(CursorInteraction::None)
-            .insert(InteractionTime::default())
+            .insert(Interactable::new("prompt.talk").with_range(4.0))
+            .insert(
+                WorldLabel::new("...")
+                    .with_offset(Vec3::Y * 0.6)
+                    .with_distance(8.0, 12.0),
+            )
             .insert(meshes.add(Mesh::from(shape::UVSphere {
                 radius: 0.3,
                 ..default()
@@ -83,15 +127,9 @@ pub fn spawn_ai(
             }))
             .insert(Visibility::default())
             .insert(ComputedVisibility::default())
-            .insert(Animator::new(Tween::new(
-                EaseFunction::SineInOut,
-                TweeningType::PingPong,
-                std::time::Duration::from_secs(2),
-                TransformPositionLens {
-                    start: vec3(trans.translation.x, 0.5, trans.translation.z),
-                    end: vec3(trans.translation.x, 1.3, trans.translation.z),
-                },
-            )))
+            .insert(AIMovement::new(trans.translation))
+            // it follows the player around, so keep it audible from the next room
+            .insert(AudioEmitter::new(3.0, 30.0))
             .insert(Name::new("Nexus"))
             .with_children(|parent| {
                 // children floating
@@ -145,131 +183,31 @@ pub fn spawn_ai(
                         }
                     });
             });
-
-        if *ai == AI::Intro {
-            commands
-                .entity(e)
-                .insert(IntroTimer(Timer::new(Duration::from_secs_f32(2.0), false)));
-        }
     }
 }
 
-#[derive(Component)]
-pub struct IntroTimer(pub Timer);
-
-fn advance_intro(
-    mut query: Query<(&mut AI, &mut IntroTimer)>,
-    time: Res<Time>,
-    mut blaster_query: Query<&mut PolarityBlaster, Without<Parent>>,
-    mut switch_query: Query<&mut Switch>,
-    mut intro_config: ResMut<AIIntroConfig>,
-    channel: Res<AudioChannel<AIAudioChannel>>,
-    audio_sources: Res<Assets<AudioSource>>,
-    mut camera_config: ResMut<CameraPlayerConfig>,
-    mut mouse_motion: EventReader<MouseMotion>,
-    mut paused: Local<bool>,
-
-    keyboard_input: Res<Input<KeyCode>>,
+fn update_interactable(
+    mut query: Query<(&AI, &mut Interactable), Or<(Changed<AI>, Added<Interactable>)>>,
 ) {
-    for (mut ai, mut intro_timer) in query.iter_mut() {
-        if intro_config.step == 0 {
-            camera_config.disable_look = true;
-            camera_config.disable_movement = true;
-        }
-
-        // hack to skip
-        if keyboard_input.just_pressed(KeyCode::S) && keyboard_input.pressed(KeyCode::LAlt) {
-            channel.stop();
-
-            camera_config.disable_look = false;
-            camera_config.disable_movement = false;
-
-            *ai = AI::Idle;
-
-            for mut b in blaster_query.iter_mut() {
-                *b = PolarityBlaster::Enabled;
-            }
-
-            // enable switch
-            for mut s in switch_query.iter_mut() {
-                s.state = SwitchState::Enabled;
-            }
-
-            intro_config.step = 12;
-        }
-
-        intro_timer.0.tick(time.delta());
-        if intro_timer.0.finished() && !*paused {
-            if let Some(handle) = intro_config.next() {
-                let source = audio_sources.get(&handle).unwrap();
-                let time = source.sound.duration() + Duration::from_secs_f32(1.0);
-                intro_timer.0 = Timer::new(time, false);
-
-                channel.play(handle).with_volume(0.4);
-            }
-        }
-
-        match intro_config.step {
-            2 => {
-                // check for mouse movement
-                camera_config.disable_look = false;
-                *paused = true;
-                let mut mouse_delta = Vec2::ZERO;
-                for mouse_event in mouse_motion.iter() {
-                    mouse_delta += mouse_event.delta;
-                }
-                if mouse_delta != Vec2::ZERO {
-                    *paused = false;
-                }
-            }
-            4 => {
-                // check for movemnt
-                camera_config.disable_movement = false;
-                *paused = true;
-                if keyboard_input.any_pressed([KeyCode::W, KeyCode::S, KeyCode::A, KeyCode::D]) {
-                    *paused = false;
-                }
-            }
-            8 => {
-                *paused = true;
-                for mut b in blaster_query.iter_mut() {
-                    *b = PolarityBlaster::Enabled;
-                }
-                if blaster_query.iter().count() == 0 {
-                    *paused = false;
-                }
-            }
-            9 => {
-                // enable switch
-                for mut s in switch_query.iter_mut() {
-                    s.state = SwitchState::Enabled;
-                }
-            }
-            11 => {
-                // enable annoy mode
-                *ai = AI::Idle;
-            }
-            _ => {}
-        }
+    for (ai, mut interactable) in query.iter_mut() {
+        interactable.disabled = match ai {
+            AI::Intro => Some("prompt.busy".to_string()),
+            AI::Idle => None,
+        };
     }
 }
 
 #[allow(clippy::single_match)]
 fn interaction_check(
-    mut query: Query<(&AI, &CursorInteraction, &mut InteractionTime)>,
-    mut annoy_config: ResMut<AIAnnoyConfig>,
-    channel: Res<AudioChannel<AIAudioChannel>>,
-    audio_sources: Res<Assets<AudioSource>>,
+    mut query: Query<(&AI, &CursorInteraction, &mut Interactable)>,
+    mut bark_events: EventWriter<BarkEvent>,
 ) {
-    for (ai, cursor_interaction, mut interaction_time) in query.iter_mut() {
+    for (ai, cursor_interaction, mut interactable) in query.iter_mut() {
         match cursor_interaction {
             CursorInteraction::Clicked => {
                 if *ai == AI::Idle {
-                    let handle = annoy_config.next();
-                    let source = audio_sources.get(&handle).unwrap();
-                    interaction_time.timer = Timer::new(source.sound.duration(), false);
-
-                    channel.play(handle).with_volume(0.4);
+                    interactable.start_cooldown(Duration::from_secs_f32(1.0));
+                    bark_events.send(BarkEvent(Bark::Poked));
                 }
             }
             _ => {
@@ -279,6 +217,22 @@ fn interaction_check(
     }
 }
 
+// bubble over the ai with whatever it's saying
+fn update_speech_bubble(
+    mut query: Query<&mut WorldLabel, With<AI>>,
+    channel: Res<AudioChannel<AIAudioChannel>>,
+    captions: Res<Captions>,
+) {
+    let talking = channel.is_playing_sound();
+    let line = captions.current::<AIAudioChannel>().unwrap_or("...");
+    for mut label in query.iter_mut() {
+        label.hidden = !talking;
+        if label.text != line {
+            label.text = line.to_string();
+        }
+    }
+}
+
 fn make_nexus_shape() -> Vec<Vec3> {
     let half_height = 0.5;
     let half_width = 0.3;
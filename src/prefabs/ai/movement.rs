@@ -0,0 +1,228 @@
+// where the ai floats, it follows the player around and flies over to whatever it's talking about
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, TAU};
+
+use bevy::prelude::*;
+use sly_physics::prelude::*;
+
+use crate::{camera::CameraMain, prefabs::Pellet, raycast::cast_through};
+
+use super::AI;
+
+pub struct AIMovementConfig {
+    // comfortable band around the player while following
+    pub follow_min: f32,
+    pub follow_max: f32,
+    // below the camera, so it floats around shoulder height
+    pub follow_drop: f32,
+    // too far behind, jump back next to the player
+    pub teleport_distance: f32,
+    // above a point of interest
+    pub hover_height: f32,
+    pub max_speed: f32,
+    pub acceleration: f32,
+    // slows down inside this distance of the target
+    pub arrive_radius: f32,
+    pub turn_speed: f32,
+    // how far ahead to look for walls
+    pub probe_distance: f32,
+    pub radius: f32,
+    pub bob_height: f32,
+    pub bob_speed: f32,
+}
+
+impl Default for AIMovementConfig {
+    fn default() -> Self {
+        Self {
+            follow_min: 2.0,
+            follow_max: 3.5,
+            follow_drop: 0.3,
+            teleport_distance: 15.0,
+            hover_height: 0.8,
+            max_speed: 3.0,
+            acceleration: 4.0,
+            arrive_radius: 1.0,
+            turn_speed: 4.0,
+            probe_distance: 1.2,
+            radius: 0.5,
+            bob_height: 0.15,
+            bob_speed: 2.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum AIBehaviour {
+    // hold a point, where it spawned to start with
+    Stay(Vec3),
+    Follow,
+    // float over to something it's talking about
+    GoTo(Entity),
+    // head for something, waiting up when the player falls behind, then follow once they're there
+    Lead(Entity),
+}
+
+#[derive(Component)]
+pub struct AIMovement {
+    pub behaviour: AIBehaviour,
+    // without the bob
+    position: Vec3,
+    velocity: Vec3,
+    time: f32,
+}
+
+impl AIMovement {
+    pub fn new(position: Vec3) -> Self {
+        Self {
+            behaviour: AIBehaviour::Stay(position),
+            position,
+            velocity: Vec3::ZERO,
+            time: 0.0,
+        }
+    }
+
+    pub fn position(&self) -> Vec3 {
+        self.position
+    }
+}
+
+// pellets and the ai itself don't block, only walls and props steer it
+struct Probe<'a, 'w, 's> {
+    tlas: &'a Tlas,
+    ignore: &'a Query<'w, 's, (), Or<(With<Pellet>, With<AI>)>>,
+}
+
+impl<'a, 'w, 's> Probe<'a, 'w, 's> {
+    // distance to the first wall along the ray
+    fn cast(&self, origin: Vec3, direction: Vec3) -> Option<f32> {
+        cast_through(self.tlas, origin, direction, |e| self.ignore.contains(e))
+    }
+
+    // turn away from walls, tries a few headings either side before giving up
+    fn steer(&self, origin: Vec3, velocity: Vec3, config: &AIMovementConfig) -> Vec3 {
+        let speed = velocity.length();
+        if speed < 1e-3 {
+            return velocity;
+        }
+        let direction = velocity / speed;
+        let reach = config.radius + config.probe_distance * (speed / config.max_speed).max(0.25);
+
+        for angle in [0.0, FRAC_PI_4, -FRAC_PI_4, FRAC_PI_2, -FRAC_PI_2] {
+            let heading = Quat::from_rotation_y(angle) * direction;
+            match self.cast(origin, heading) {
+                Some(distance) if distance < reach => continue,
+                _ => return heading * speed,
+            }
+        }
+        Vec3::ZERO
+    }
+}
+
+pub fn move_ai(
+    mut query: Query<(&mut AIMovement, &mut Transform), Without<CameraMain>>,
+    camera_query: Query<&Transform, With<CameraMain>>,
+    target_query: Query<&GlobalTransform>,
+    ignore_query: Query<(), Or<(With<Pellet>, With<AI>)>>,
+    config: Res<AIMovementConfig>,
+    tlas: Res<Tlas>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    let camera_trans = match camera_query.get_single() {
+        Ok(camera) => camera,
+        Err(_) => return,
+    };
+    let camera = camera_trans.translation;
+    let probe = Probe {
+        tlas: &tlas,
+        ignore: &ignore_query,
+    };
+
+    for (mut movement, mut trans) in query.iter_mut() {
+        let movement = &mut *movement;
+        movement.time += dt;
+
+        let target = match movement.behaviour {
+            AIBehaviour::Stay(point) => Some(point),
+            AIBehaviour::GoTo(e) => match target_query.get(e) {
+                Ok(target) => Some(target.translation() + Vec3::Y * config.hover_height),
+                // it went away, tag along with the player instead
+                Err(_) => {
+                    movement.behaviour = AIBehaviour::Follow;
+                    None
+                }
+            },
+            AIBehaviour::Lead(e) => match target_query.get(e) {
+                Ok(target) => {
+                    let target = target.translation() + Vec3::Y * config.hover_height;
+                    let player_distance = movement.position.distance(camera);
+                    if movement.position.distance(target) < config.arrive_radius
+                        && player_distance < config.follow_max
+                    {
+                        movement.behaviour = AIBehaviour::Follow;
+                        None
+                    } else if player_distance > config.follow_max * 2.0 {
+                        // wait up
+                        Some(movement.position)
+                    } else {
+                        Some(target)
+                    }
+                }
+                Err(_) => {
+                    movement.behaviour = AIBehaviour::Follow;
+                    None
+                }
+            },
+            AIBehaviour::Follow => {
+                let mut away = movement.position - camera;
+                away.y = 0.0;
+                let distance = away.length();
+
+                if distance > config.teleport_distance {
+                    // lost, pop back in behind the player where they won't see it happen
+                    let mut behind = camera_trans.back();
+                    behind.y = 0.0;
+                    let behind = behind.normalize_or_zero() * config.follow_min;
+                    movement.position = camera + behind - Vec3::Y * config.follow_drop;
+                    movement.velocity = Vec3::ZERO;
+                    None
+                } else {
+                    // stay on whatever side we're on, just pull into the band
+                    let direction = if distance > 1e-3 {
+                        away / distance
+                    } else {
+                        Vec3::X
+                    };
+                    let distance = distance.clamp(config.follow_min, config.follow_max);
+                    Some(camera + direction * distance - Vec3::Y * config.follow_drop)
+                }
+            }
+        };
+
+        let desired = match target {
+            Some(target) => {
+                let to_target = target - movement.position;
+                let distance = to_target.length();
+                let speed = config.max_speed * (distance / config.arrive_radius).min(1.0);
+                to_target.normalize_or_zero() * speed
+            }
+            None => Vec3::ZERO,
+        };
+        let desired = probe.steer(movement.position, desired, &config);
+
+        let blend = (config.acceleration * dt).min(1.0);
+        movement.velocity = movement.velocity.lerp(desired, blend);
+        movement.position += movement.velocity * dt;
+
+        let bob = (movement.time * config.bob_speed % TAU).sin() * config.bob_height;
+        trans.translation = movement.position + Vec3::Y * bob;
+
+        // keep an eye on the player
+        let mut look = camera - movement.position;
+        look.y = 0.0;
+        if look.length_squared() > 1e-4 {
+            let facing = Transform::default().looking_at(look, Vec3::Y).rotation;
+            let turn = (config.turn_speed * dt).min(1.0);
+            trans.rotation = trans.rotation.slerp(facing, turn);
+        }
+    }
+}
@@ -1,14 +1,17 @@
 use std::{ops::Range, time::Duration};
 
-use super::{space_kit::*, AIAudioChannel, AIHighConfig, Pellet, PelletConfig, SwitchEvent};
+use super::{
+    space_kit::*, Bark, BarkEvent, Grabbable, Pellet, PelletConfig, RoomConfig, SwitchEvent,
+};
 use crate::{
-    assets::{AIAudioAssets, CLEAR},
-    states::{GameTimer, HighScore, Score},
+    assets::CLEAR,
+    cinematic::{CameraKey, CameraPath, StartCinematic},
+    states::{GameTimer, HighScore, PauseState, Score},
     GameState,
 };
 use bevy::{math::vec3, prelude::*};
-use bevy_kira_audio::{AudioChannel, AudioControl};
 use bevy_mod_outline::{Outline, OutlineBundle};
+use bevy_tweening::EaseFunction;
 use iyes_loopless::prelude::*;
 use rand::Rng;
 use sly_physics::prelude::*;
@@ -18,10 +21,23 @@ pub struct DispenserPlugin;
 impl Plugin for DispenserPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<GameTimer>()
+            .add_event::<RoundEvent>()
             .add_system(spawn_dispenser.run_in_state(GameState::Playing))
-            .add_system(switch_event.run_in_state(GameState::Playing))
-            .add_system(dispense_pellets.run_in_state(GameState::Playing))
-            .add_system(update_game_timer.run_in_state(GameState::Playing));
+            .add_system(
+                switch_event
+                    .run_in_state(GameState::Playing)
+                    .run_in_state(PauseState::Running),
+            )
+            .add_system(
+                dispense_pellets
+                    .run_in_state(GameState::Playing)
+                    .run_in_state(PauseState::Running),
+            )
+            .add_system(
+                update_game_timer
+                    .run_in_state(GameState::Playing)
+                    .run_in_state(PauseState::Running),
+            );
     }
 }
 
@@ -46,6 +62,12 @@ impl Default for Dispenser {
     }
 }
 
+pub enum RoundEvent {
+    Started,
+    // score is what was stabilized before the timer ran out, 0 means meltdown
+    Finished { score: u32 },
+}
+
 fn spawn_dispenser(mut commands: Commands, query: Query<Entity, Added<Dispenser>>) {
     for e in query.iter() {
         commands
@@ -62,20 +84,47 @@ fn update_game_timer(
     mut score: ResMut<Score>,
     mut high_score: ResMut<HighScore>,
     pellet_query: Query<Entity, With<Pellet>>,
-    mut high_config: ResMut<AIHighConfig>,
-    channel: Res<AudioChannel<AIAudioChannel>>,
+    mut cinematic_events: EventWriter<StartCinematic>,
+    room_config: Res<RoomConfig>,
+    mut bark_events: EventWriter<BarkEvent>,
+    mut round_events: EventWriter<RoundEvent>,
 ) {
     game_timer.0.tick(time.delta());
 
     if game_timer.0.just_finished() {
+        round_events.send(RoundEvent::Finished { score: score.0 });
         if score.0 > high_score.0 {
-            let handle = high_config.next();
-            channel.play(handle).with_volume(0.4);
+            bark_events.send(BarkEvent(Bark::HighScore));
+        }
+        // nothing was stabilized, show the reactor going critical
+        if score.0 == 0 {
+            cinematic_events.send(StartCinematic(meltdown_path(&room_config)));
         }
         reset(&mut score, &mut high_score, &pellet_query, &mut commands);
     }
 }
 
+// pulls out over the landing, pushes into the reactor core, then hands the camera back
+fn meltdown_path(room_config: &RoomConfig) -> CameraPath {
+    let reactor = vec3(
+        0.0,
+        room_config.wall_height_half,
+        room_config.reactor_center_z,
+    );
+    let landing_end = room_config.intro_floor_half + room_config.landing_floor_size.y;
+
+    CameraPath {
+        keys: vec![
+            CameraKey::new(vec3(0.0, 3.5, landing_end - 1.0), reactor, 1.5),
+            CameraKey::new(reactor - vec3(0.0, 0.0, 3.0), reactor, 3.0)
+                .with_ease(EaseFunction::QuadraticIn),
+        ],
+        return_to_start: true,
+        return_duration: 1.5,
+        skippable: true,
+    }
+}
+
 fn switch_event(
     mut commands: Commands,
     mut switch_events: EventReader<SwitchEvent>,
@@ -83,9 +132,9 @@ fn switch_event(
     mut score: ResMut<Score>,
     mut high_score: ResMut<HighScore>,
     pellet_query: Query<Entity, With<Pellet>>,
-    channel: Res<AudioChannel<AIAudioChannel>>,
-    audio_assets: Res<AIAudioAssets>,
     mut game_timer: ResMut<GameTimer>,
+    mut bark_events: EventWriter<BarkEvent>,
+    mut round_events: EventWriter<RoundEvent>,
 ) {
     for switch_event in switch_events.iter() {
         let dispenser_entity = switch_event.0;
@@ -95,7 +144,8 @@ fn switch_event(
             game_timer.0.set_duration(Duration::from_secs(30));
             game_timer.0.reset();
 
-            channel.play(audio_assets.start.clone());
+            bark_events.send(BarkEvent(Bark::RoundStart));
+            round_events.send(RoundEvent::Started);
 
             reset(&mut score, &mut high_score, &pellet_query, &mut commands);
 
@@ -177,6 +227,7 @@ fn dispense_pellets(
                     },
                     ..default()
                 })
+                .insert(Grabbable)
                 .insert(Name::new("Pellet"));
 
             if dispenser.count > 0 {
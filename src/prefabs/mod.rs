@@ -4,9 +4,11 @@ mod door;
 mod pellet;
 mod polarity_blaster;
 mod reactor;
+mod respawn;
 mod rooms;
 mod space_kit;
 mod switch;
+mod tractor_beam;
 
 pub use self::reactor::*;
 pub use ai::*;
@@ -14,9 +16,11 @@ pub use dispenser::*;
 pub use door::*;
 pub use pellet::*;
 pub use polarity_blaster::*;
+pub use respawn::*;
 pub use rooms::*;
 pub use space_kit::*;
 pub use switch::*;
+pub use tractor_beam::*;
 
 use bevy::prelude::*;
 
@@ -32,6 +36,8 @@ impl Plugin for PrefabPlugin {
             .add_plugin(PolarityBlasterPlugin)
             .add_plugin(PelletPlugin)
             .add_plugin(DispenserPlugin)
-            .add_plugin(ReactorPlugin);
+            .add_plugin(ReactorPlugin)
+            .add_plugin(RespawnPlugin)
+            .add_plugin(TractorBeamPlugin);
     }
 }
@@ -1,25 +1,54 @@
-use crate::GameState;
+use crate::{labels::WorldLabel, locale::Strings, states::PauseState, GameState};
 use bevy::{math::vec3, prelude::*};
 use bevy_inspector_egui::prelude::*;
 use bevy_mod_outline::{Outline, OutlineMeshExt};
 use iyes_loopless::prelude::*;
 use sly_physics::prelude::*;
 
+use super::Held;
+
 pub struct PelletPlugin;
 
 impl Plugin for PelletPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<PelletConfig>()
+            .add_event::<PelletEvent>()
             //.add_system(spawn_pellet.run_in_state(GameState::Playing))
+            .add_system(add_label.run_in_state(GameState::Playing))
+            .add_system(update_label.run_in_state(GameState::Playing))
             .add_system_to_stage(
                 CoreStage::Last,
-                update_pellet.run_in_state(GameState::Playing),
+                update_pellet
+                    .run_in_state(GameState::Playing)
+                    .run_in_state(PauseState::Running),
             )
             .add_system_to_stage(CoreStage::First, clear_hit.run_in_state(GameState::Playing))
             .register_inspectable::<Pellet>();
     }
 }
 
+// hover readout with the exact polarity
+fn add_label(mut commands: Commands, query: Query<(Entity, &Pellet), Added<Pellet>>) {
+    for (e, pellet) in query.iter() {
+        commands.entity(e).insert(
+            WorldLabel::new("")
+                .hover()
+                .with_bar(pellet.value, pellet.color()),
+        );
+    }
+}
+
+fn update_label(mut query: Query<(&Pellet, &mut WorldLabel)>, strings: Res<Strings>) {
+    for (pellet, mut label) in query.iter_mut() {
+        label.text = strings.format(
+            "label.polarity",
+            &[("value", format!("{:.2}", pellet.value))],
+        );
+        label.bar = Some(pellet.value);
+        label.bar_color = pellet.color();
+    }
+}
+
 fn clear_hit(mut query: Query<&mut Pellet>) {
     for mut pellet in query.iter_mut() {
         pellet.hit = false;
@@ -32,11 +61,12 @@ fn update_pellet(
         &mut Outline,
         &mut LinearVelocity,
         &Handle<StandardMaterial>,
+        Option<&Held>,
     )>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     pellet_config: Res<PelletConfig>,
 ) {
-    for (pellet, mut outline, mut lin_vel, material_handle) in query.iter_mut() {
+    for (pellet, mut outline, mut lin_vel, material_handle, held) in query.iter_mut() {
         let mat = materials.get_mut(material_handle).unwrap();
         mat.base_color = pellet.color();
 
@@ -46,6 +76,11 @@ fn update_pellet(
             outline.colour = Color::RED;
         };
 
+        // tractor beam is in control
+        if held.is_some() {
+            continue;
+        }
+
         // speed up pellet if it's to slow
         if lin_vel.0.x.abs() < 1.0 {
             if lin_vel.0.x.is_sign_positive() {
@@ -87,6 +122,12 @@ impl FromWorld for PelletConfig {
     }
 }
 
+// Sent when a pellet's polarity moves into or out of the allowed range
+pub enum PelletEvent {
+    Stabilized,
+    Lost,
+}
+
 #[derive(Component, bevy_inspector_egui::Inspectable)]
 pub struct Pellet {
     pub hit: bool,
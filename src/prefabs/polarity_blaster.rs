@@ -10,14 +10,15 @@ use iyes_loopless::prelude::*;
 use sly_physics::prelude::*;
 
 use crate::{
+    actions::{Action, ActionState},
     assets::SpaceKitAssets,
     camera::{CameraMain, CameraState},
     cursor::*,
-    states::Score,
+    states::{PauseState, Score},
     GameState,
 };
 
-use super::{Pellet, PelletConfig};
+use super::{Pellet, PelletConfig, PelletEvent};
 
 #[derive(Component)]
 pub enum PolarityBlaster {
@@ -31,14 +32,22 @@ impl Plugin for PolarityBlasterPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<PolarityBlasterConfig>()
             .add_system(spawn_blaster.run_in_state(GameState::Playing))
+            .add_system(
+                update_interactable
+                    .run_in_state(GameState::Playing)
+                    .after(spawn_blaster),
+            )
             .add_system_to_stage(
                 CoreStage::PostUpdate,
-                interaction_check.run_in_state(GameState::Playing),
+                interaction_check
+                    .run_in_state(GameState::Playing)
+                    .run_in_state(PauseState::Running),
             )
             .add_system_to_stage(
                 CoreStage::PostUpdate,
                 fire_blaster
                     .run_in_state(GameState::Playing)
+                    .run_in_state(PauseState::Running)
                     .run_in_state(CameraState::Player),
             );
     }
@@ -109,22 +118,24 @@ pub fn fire_blaster(
     mut commands: Commands,
     query: Query<(Entity, &PolarityBlaster), With<Parent>>,
     camera_query: Query<&Transform, With<CameraMain>>,
-    mouse_input: Res<Input<MouseButton>>,
+    actions: Res<ActionState>,
     mut laser_query: Query<Entity, With<Laser>>,
     tlas: Res<Tlas>,
     mut pellet_query: Query<&mut Pellet>,
     pellet_config: Res<PelletConfig>,
     config: Res<PolarityBlasterConfig>,
     mut score: ResMut<Score>,
+    mut pellet_events: EventWriter<PelletEvent>,
 ) {
     let camera_trans = camera_query.single();
 
     for (_entity, _blaster) in query.iter() {
         let mut hit_type = HitType::None;
-        if mouse_input.pressed(MouseButton::Left) && !mouse_input.pressed(MouseButton::Right) {
+        let blue = actions.pressed(Action::FireBlue);
+        let yellow = actions.pressed(Action::FireYellow);
+        if blue && !yellow {
             hit_type = HitType::Blue;
-        } else if mouse_input.pressed(MouseButton::Right) && !mouse_input.pressed(MouseButton::Left)
-        {
+        } else if yellow && !blue {
             hit_type = HitType::Yellow;
         }
 
@@ -145,21 +156,26 @@ pub fn fire_blaster(
             if let Some(hit) = ray.intersect_tlas(&tlas) {
                 if let Ok(mut pellet) = pellet_query.get_mut(hit.entity) {
                     let was_in_range = (pellet.value - 0.5).abs() < pellet_config.allow_range;
+                    // analog triggers fire slower when only partly pulled
                     match hit_type {
                         HitType::Blue => {
-                            pellet.value = (pellet.value - config.hit_change).clamp(0.0, 1.0);
+                            let change = config.hit_change * actions.value(Action::FireBlue);
+                            pellet.value = (pellet.value - change).clamp(0.0, 1.0);
                         }
                         HitType::Yellow => {
-                            pellet.value = (pellet.value + config.hit_change).clamp(0.0, 1.0);
+                            let change = config.hit_change * actions.value(Action::FireYellow);
+                            pellet.value = (pellet.value + change).clamp(0.0, 1.0);
                         }
                         HitType::None => unreachable!(),
                     }
                     let in_range = (pellet.value - 0.5).abs() < pellet_config.allow_range;
                     if !was_in_range && in_range {
                         score.0 += 1;
+                        pellet_events.send(PelletEvent::Stabilized);
                     }
                     if was_in_range && !in_range {
                         score.0 -= 1;
+                        pellet_events.send(PelletEvent::Lost);
                     }
 
                     pellet.hit = true;
@@ -184,7 +200,7 @@ pub fn spawn_blaster(
     mut collider_resources: ResMut<ColliderResources>,
     config: Res<PolarityBlasterConfig>,
 ) {
-    for (e, mut _blaster) in query.iter_mut() {
+    for (e, _blaster) in query.iter_mut() {
         if let Some(gltf) = assets_gltf.get(&spacekit.weapon_blaster_r_gltf) {
             if let Some(gltf_mesh) = assets_gltf_mesh.get(&gltf.meshes[0]) {
                 commands
@@ -197,8 +213,7 @@ pub fn spawn_blaster(
                         },
                         ..default()
                     })
-                    .insert(CursorInteraction::None)
-                    .insert(InteractionTime::default())
+                    .insert(Interactable::new("prompt.pick_up"))
                     .insert_bundle(RigidBodyBundle {
                         collider: collider_resources.add_box(config.collider),
                         mode: RigidBodyMode::Static,
@@ -246,54 +261,48 @@ pub fn spawn_blaster(
     }
 }
 
+fn update_interactable(
+    mut query: Query<
+        (&PolarityBlaster, &mut Interactable),
+        Or<(Changed<PolarityBlaster>, Added<Interactable>)>,
+    >,
+) {
+    for (blaster, mut interactable) in query.iter_mut() {
+        interactable.disabled = match blaster {
+            PolarityBlaster::Disabled => Some("prompt.locked".to_string()),
+            PolarityBlaster::Enabled => None,
+        };
+    }
+}
+
 fn interaction_check(
     mut commands: Commands,
-    mut query: Query<(
-        Entity,
-        &PolarityBlaster,
-        &mut Transform,
-        &Children,
-        &CursorInteraction,
-        &mut InteractionTime,
-    )>,
+    mut query: Query<
+        (Entity, &mut Transform, &Children, &CursorInteraction),
+        With<PolarityBlaster>,
+    >,
     mut outline_query: Query<&mut Outline>,
-    cursor_config: Res<CursorConfig>,
     camera_query: Query<Entity, With<CameraMain>>,
     config: Res<PolarityBlasterConfig>,
 ) {
-    for (e, blaster, mut trans, children, cursor_interaction, mut _interaction_time) in
-        query.iter_mut()
-    {
-        match cursor_interaction {
-            CursorInteraction::Hovered => {
-                for c in children.iter() {
-                    if let Ok(mut outline) = outline_query.get_mut(*c) {
-                        outline.visible = true;
-                        outline.colour = match blaster {
-                            PolarityBlaster::Disabled => cursor_config.disabled,
-                            PolarityBlaster::Enabled => cursor_config.hover,
-                        };
-                    }
-                }
-            }
-            CursorInteraction::Clicked => {
-                match blaster {
-                    PolarityBlaster::Disabled => {}
-                    PolarityBlaster::Enabled => {
-                        // pick up
-                        let camera_entity = camera_query.single();
-                        commands.entity(camera_entity).push_children(&[e]);
+    for (e, mut trans, children, cursor_interaction) in query.iter_mut() {
+        // disabled blasters never get clicked
+        if let CursorInteraction::Clicked = cursor_interaction {
+            // pick up
+            let camera_entity = camera_query.single();
+            commands.entity(camera_entity).push_children(&[e]);
 
-                        trans.translation = config.offset;
-                        trans.rotation = Quat::IDENTITY;
-                    }
-                }
-            }
-            CursorInteraction::None => {
-                for c in children.iter() {
-                    if let Ok(mut outline) = outline_query.get_mut(*c) {
-                        outline.visible = false;
-                    }
+            trans.translation = config.offset;
+            trans.rotation = Quat::IDENTITY;
+
+            // in hand now, nothing left to interact with
+            commands
+                .entity(e)
+                .remove::<Interactable>()
+                .remove::<CursorInteraction>();
+            for c in children.iter() {
+                if let Ok(mut outline) = outline_query.get_mut(*c) {
+                    outline.visible = false;
                 }
             }
         }
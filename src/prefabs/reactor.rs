@@ -1,33 +1,81 @@
-use crate::GameState;
+use crate::{
+    assets::AudioAssets,
+    mixer::{fade, Bus, Mixer, MixerApp, MixerChannel},
+    spatial_audio::{AudioEmitter, ChannelEmitter, SpatialAudioApp},
+    states::PauseState,
+    GameState,
+};
 use bevy::{math::vec3, prelude::*};
+use bevy_kira_audio::prelude::*;
 use iyes_loopless::prelude::*;
 use sly_physics::prelude::*;
 
-use super::{space_kit::*, Pellet, RoomConfig};
+use super::{space_kit::*, Held, Pellet, RoomConfig};
 
 pub struct ReactorPlugin;
 
 impl Plugin for ReactorPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system(spawn_reactor.run_in_state(GameState::Playing))
-            .add_system(pellet_gravity_system.run_in_state(GameState::Playing));
+        app.add_mixer_channel::<ReactorAudioChannel>()
+            .add_spatial_channel::<ReactorAudioChannel>()
+            .add_system(spawn_reactor.run_in_state(GameState::Playing))
+            .add_system(
+                start_hum
+                    .run_in_state(GameState::Playing)
+                    .after(spawn_reactor),
+            )
+            .add_system(
+                pellet_gravity_system
+                    .run_in_state(GameState::Playing)
+                    .run_in_state(PauseState::Running),
+            );
     }
 }
 
 #[derive(Component)]
 pub struct Reactor;
 
+pub struct ReactorAudioChannel;
+
+impl MixerChannel for ReactorAudioChannel {
+    const BUS: Bus = Bus::Sfx;
+}
+
 fn spawn_reactor(mut commands: Commands, query: Query<Entity, Added<Reactor>>) {
     for e in query.iter() {
         commands
             .entity(e)
             .insert(Name::new("Reactor"))
-            .insert(SpaceKit::Rocket(Rocket::BaseB));
+            .insert(SpaceKit::Rocket(Rocket::BaseB))
+            // heard from the other rooms, muffled through the walls
+            .insert(AudioEmitter::new(4.0, 40.0));
+    }
+}
+
+// looped from the reactor so you can find it by ear, one channel so only the first reactor hums
+fn start_hum(
+    query: Query<Entity, Added<Reactor>>,
+    audio_assets: Res<AudioAssets>,
+    channel: Res<AudioChannel<ReactorAudioChannel>>,
+    mut emitter: ResMut<ChannelEmitter<ReactorAudioChannel>>,
+    mixer: Res<Mixer>,
+) {
+    if let Some(e) = query.iter().next() {
+        emitter.play_from(e);
+        channel.stop();
+        channel
+            .play(audio_assets.reactor_hum.clone())
+            .looped()
+            .with_volume(mixer.volume::<ReactorAudioChannel>())
+            .fade_in(fade(2.0));
     }
 }
 
 pub fn pellet_gravity_system(
-    mut query: Query<(&mut LinearVelocity, &Mass, &InverseMass, &Transform), With<Pellet>>,
+    mut query: Query<
+        (&mut LinearVelocity, &Mass, &InverseMass, &Transform),
+        (With<Pellet>, Without<Held>),
+    >,
     room_config: Res<RoomConfig>,
     physics_config: Res<PhysicsConfig>,
 ) {
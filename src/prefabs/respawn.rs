@@ -0,0 +1,222 @@
+use bevy::{prelude::*, ui::FocusPolicy};
+use iyes_loopless::prelude::*;
+
+use crate::{
+    assets::CLEAR,
+    camera::{CameraMain, CameraState},
+    character::CharacterController,
+    states::PauseState,
+    GameState,
+};
+
+pub struct RespawnPlugin;
+
+impl Plugin for RespawnPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RespawnConfig>()
+            .init_resource::<Respawn>()
+            .add_event::<RespawnEvent>()
+            .add_enter_system(GameState::Playing, setup_fade)
+            .add_system(set_respawn_point.run_in_state(GameState::Playing))
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                check_checkpoints
+                    .run_in_state(GameState::Playing)
+                    .run_in_state(PauseState::Running)
+                    .run_in_state(CameraState::Player),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                check_kill
+                    .run_in_state(GameState::Playing)
+                    .run_in_state(PauseState::Running)
+                    .run_in_state(CameraState::Player),
+            )
+            .add_system(
+                update_fade
+                    .run_in_state(GameState::Playing)
+                    .run_in_state(PauseState::Running),
+            )
+            .add_exit_system(GameState::Playing, reset_respawn);
+    }
+}
+
+// Box centered on the transform, touching it kills the player
+#[derive(Component)]
+pub struct KillVolume {
+    pub size: Vec3,
+}
+
+// Box centered on the transform, entering it moves the respawn point to the transform
+#[derive(Component)]
+pub struct Checkpoint {
+    pub size: Vec3,
+}
+
+// Where the player respawns until they reach a checkpoint, one per level
+#[derive(Component)]
+pub struct RespawnPoint;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RespawnReason {
+    Fell,
+    Hazard,
+}
+
+// Sent when the player dies, before the screen fades out
+pub struct RespawnEvent(pub RespawnReason);
+
+pub struct RespawnConfig {
+    // anything below this counts as falling off the map
+    pub fall_height: f32,
+    pub fade_out: f32,
+    pub fade_in: f32,
+}
+
+impl Default for RespawnConfig {
+    fn default() -> Self {
+        Self {
+            fall_height: -10.0,
+            fade_out: 0.5,
+            fade_in: 0.5,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Respawn {
+    pub checkpoint: Option<Transform>,
+    fade: Fade,
+}
+
+enum Fade {
+    None,
+    Out(Timer),
+    In(Timer),
+}
+
+impl Default for Fade {
+    fn default() -> Self {
+        Fade::None
+    }
+}
+
+#[derive(Component)]
+struct FadeOverlay;
+
+fn setup_fade(mut commands: Commands) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                ..default()
+            },
+            color: CLEAR.into(),
+            focus_policy: FocusPolicy::Pass,
+            ..default()
+        })
+        .insert(Name::new("ui Fade"))
+        .insert(FadeOverlay);
+}
+
+fn set_respawn_point(query: Query<&Transform, Added<RespawnPoint>>, mut respawn: ResMut<Respawn>) {
+    for trans in query.iter() {
+        respawn.checkpoint = Some(*trans);
+    }
+}
+
+fn check_checkpoints(
+    camera_query: Query<&Transform, With<CameraMain>>,
+    checkpoint_query: Query<(&Checkpoint, &Transform), Without<CameraMain>>,
+    mut respawn: ResMut<Respawn>,
+) {
+    let camera_trans = camera_query.single();
+    for (checkpoint, trans) in checkpoint_query.iter() {
+        if inside(camera_trans.translation, trans.translation, checkpoint.size) {
+            respawn.checkpoint = Some(*trans);
+        }
+    }
+}
+
+fn check_kill(
+    camera_query: Query<&Transform, With<CameraMain>>,
+    kill_query: Query<(&KillVolume, &Transform), Without<CameraMain>>,
+    mut respawn: ResMut<Respawn>,
+    config: Res<RespawnConfig>,
+    mut respawn_events: EventWriter<RespawnEvent>,
+) {
+    if !matches!(respawn.fade, Fade::None) {
+        return;
+    }
+
+    let camera_trans = camera_query.single();
+    let reason = if camera_trans.translation.y < config.fall_height {
+        Some(RespawnReason::Fell)
+    } else if kill_query
+        .iter()
+        .any(|(volume, trans)| inside(camera_trans.translation, trans.translation, volume.size))
+    {
+        Some(RespawnReason::Hazard)
+    } else {
+        None
+    };
+
+    if let Some(reason) = reason {
+        respawn.fade = Fade::Out(Timer::from_seconds(config.fade_out, false));
+        respawn_events.send(RespawnEvent(reason));
+    }
+}
+
+fn update_fade(
+    mut respawn: ResMut<Respawn>,
+    mut overlay_query: Query<&mut UiColor, With<FadeOverlay>>,
+    mut camera_query: Query<(&mut Transform, &mut CharacterController), With<CameraMain>>,
+    config: Res<RespawnConfig>,
+    time: Res<Time>,
+) {
+    let respawn = &mut *respawn;
+    let alpha = match &mut respawn.fade {
+        Fade::None => return,
+        Fade::Out(timer) => {
+            timer.tick(time.delta());
+            if timer.finished() {
+                // move player while the screen is black
+                if let Some(checkpoint) = respawn.checkpoint {
+                    for (mut trans, mut controller) in camera_query.iter_mut() {
+                        *trans = checkpoint;
+                        controller.velocity = Vec3::ZERO;
+                        controller.grounded = false;
+                    }
+                }
+                respawn.fade = Fade::In(Timer::from_seconds(config.fade_in, false));
+                1.0
+            } else {
+                timer.percent()
+            }
+        }
+        Fade::In(timer) => {
+            timer.tick(time.delta());
+            if timer.finished() {
+                respawn.fade = Fade::None;
+                0.0
+            } else {
+                1.0 - timer.percent()
+            }
+        }
+    };
+
+    for mut color in overlay_query.iter_mut() {
+        color.0 = Color::rgba(0.0, 0.0, 0.0, alpha);
+    }
+}
+
+fn reset_respawn(mut respawn: ResMut<Respawn>) {
+    *respawn = Respawn::default();
+}
+
+fn inside(point: Vec3, center: Vec3, size: Vec3) -> bool {
+    let local = (point - center).abs();
+    let half = size * 0.5;
+    local.x <= half.x && local.y <= half.y && local.z <= half.z
+}
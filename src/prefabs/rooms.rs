@@ -7,9 +7,9 @@ use bevy::{
 use iyes_loopless::prelude::*;
 use sly_physics::prelude::*;
 
-use crate::{assets::TextureAssets, GameState};
+use crate::{assets::TextureAssets, labels::WorldLabel, GameState};
 
-use super::{Dispenser, Door, DoorConfig, Reactor, Switch, SwitchState};
+use super::{Dispenser, Door, DoorConfig, KillVolume, Reactor, Switch, SwitchState};
 
 pub struct RoomPlugin;
 
@@ -263,7 +263,9 @@ pub fn spawn_training_room(
         .insert(Switch {
             target: door_id,
             state: SwitchState::Disabled,
-        });
+        })
+        .insert(Name::new("Door Switch"))
+        .insert(WorldLabel::translated("label.reactor_door"));
 }
 
 pub fn spawn_reactor_room(
@@ -409,6 +411,27 @@ pub fn spawn_reactor_room(
             });
     }
 
+    // anything that falls off the landing ends up in the reactor
+    let landing_end = landing_offset + room_config.landing_floor_size.y;
+    let reactor_far = room_config.reactor_center_z + room_config.reactor_radius + 2.0;
+    commands
+        .spawn_bundle(SpatialBundle {
+            transform: Transform::from_xyz(
+                0.0,
+                room_config.wall_height_half,
+                (landing_end + reactor_far) * 0.5,
+            ),
+            ..default()
+        })
+        .insert(KillVolume {
+            size: vec3(
+                room_config.reactor_length,
+                (room_config.reactor_radius + 2.0) * 2.0,
+                reactor_far - landing_end,
+            ),
+        })
+        .insert(Name::new("Reactor Kill Volume"));
+
     // reactor end caps
     let reactor_end = vec3(
         room_config.reactor_length * 0.5,
@@ -492,7 +515,9 @@ pub fn spawn_reactor_room(
         .insert(Switch {
             target: dispenser,
             state: SwitchState::Enabled,
-        });
+        })
+        .insert(Name::new("Dispenser Switch"))
+        .insert(WorldLabel::translated("label.pellet_dispenser"));
 
     commands.spawn_bundle(PointLightBundle {
         transform: Transform {
@@ -1,7 +1,11 @@
 use std::time::Duration;
 
 use crate::{
-    cursor::{CursorConfig, CursorInteraction, InteractionTime},
+    cursor::{CursorConfig, CursorInteraction, Interactable},
+    mixer::{Bus, Mixer, MixerApp, MixerChannel},
+    spatial_audio::{AudioEmitter, ChannelEmitter, SpatialAudioApp},
+    states::PauseState,
+    subtitles::{CaptionApp, PlayCaption},
     GameState,
 };
 use bevy::{math::vec3, prelude::*};
@@ -10,10 +14,12 @@ use bevy_kira_audio::prelude::*;
 use bevy_kira_audio::AudioSource;
 use bevy_mod_outline::*;
 use iyes_loopless::prelude::*;
+use serde::Deserialize;
 use sly_physics::prelude::*;
 
 pub struct SwitchPlugin;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
 pub enum SwitchState {
     Enabled,
     Disabled,
@@ -31,17 +37,26 @@ impl Plugin for SwitchPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<SwitchConfig>()
             .add_event::<SwitchEvent>()
-            .add_audio_channel::<SwitchAudioChannel>()
+            .add_mixer_channel::<SwitchAudioChannel>()
+            .add_spatial_channel::<SwitchAudioChannel>()
+            .add_caption_channel::<SwitchAudioChannel>()
             .add_system(spawn_switch)
+            .add_system(update_interactable.after(spawn_switch))
             .add_system_to_stage(
                 CoreStage::PostUpdate,
-                interaction_check.run_in_state(GameState::Playing),
+                interaction_check
+                    .run_in_state(GameState::Playing)
+                    .run_in_state(PauseState::Running),
             );
     }
 }
 
 pub struct SwitchAudioChannel;
 
+impl MixerChannel for SwitchAudioChannel {
+    const BUS: Bus = Bus::Sfx;
+}
+
 #[derive(AssetCollection)]
 pub struct SwitchAudioAssets {
     #[asset(path = "audio/switch11.ogg")]
@@ -110,15 +125,19 @@ impl FromWorld for SwitchConfig {
 
 pub fn spawn_switch(
     mut commands: Commands,
-    query: Query<Entity, Added<Switch>>,
+    query: Query<(Entity, Option<&Name>), Added<Switch>>,
     config: Res<SwitchConfig>,
     cursor_config: Res<CursorConfig>,
 ) {
-    for e in query.iter() {
+    for (e, name) in query.iter() {
+        // keep names given by the level, dialogue scripts find switches by them
+        if name.is_none() {
+            commands.entity(e).insert(Name::new("Switch"));
+        }
+
         // add switch boarder
         commands
             .entity(e)
-            .insert(Name::new("Switch"))
             .insert(config.boarder_mesh.clone())
             .insert(config.boarder_mat.clone())
             .insert(Visibility::default())
@@ -131,8 +150,8 @@ pub fn spawn_switch(
                 },
                 ..default()
             })
-            .insert(CursorInteraction::None)
-            .insert(InteractionTime::default())
+            .insert(Interactable::new("prompt.flip"))
+            .insert(AudioEmitter::new(1.0, 15.0))
             .insert_bundle(RigidBodyBundle {
                 mode: RigidBodyMode::Static,
                 collider: config.collider.clone(),
@@ -156,49 +175,42 @@ pub fn spawn_switch(
     }
 }
 
+fn update_interactable(
+    mut query: Query<(&Switch, &mut Interactable), Or<(Changed<Switch>, Added<Interactable>)>>,
+) {
+    for (switch, mut interactable) in query.iter_mut() {
+        interactable.disabled = match switch.state {
+            SwitchState::Enabled => None,
+            SwitchState::Disabled => Some("prompt.no_power".to_string()),
+        };
+    }
+}
+
 fn interaction_check(
-    mut query: Query<(
-        &Switch,
-        &CursorInteraction,
-        &mut InteractionTime,
-        &mut Outline,
-    )>,
+    mut query: Query<(Entity, &Switch, &CursorInteraction, &mut Interactable)>,
     audio_assets: Res<SwitchAudioAssets>,
     channel: Res<AudioChannel<SwitchAudioChannel>>,
+    mut emitter: ResMut<ChannelEmitter<SwitchAudioChannel>>,
+    mixer: Res<Mixer>,
     mut switch_events: EventWriter<SwitchEvent>,
-    cursor_config: Res<CursorConfig>,
+    mut caption_events: EventWriter<PlayCaption>,
 ) {
-    for (switch, cursor_interaction, mut interaction_time, mut outline) in query.iter_mut() {
-        match cursor_interaction {
-            CursorInteraction::Clicked => {
-                match switch.state {
-                    SwitchState::Enabled => {
-                        // Play sound
-                        let handle = audio_assets.flip.clone();
-                        channel.play(handle).with_volume(0.4);
-
-                        // Set interaction timer
-                        interaction_time
-                            .timer
-                            .set_duration(Duration::from_secs_f32(1.0));
-                        interaction_time.timer.reset();
-
-                        // send event to target
-                        switch_events.send(SwitchEvent(switch.target));
-                    }
-                    SwitchState::Disabled => {}
-                }
-            }
-            CursorInteraction::Hovered => {
-                outline.visible = true;
-                outline.colour = match switch.state {
-                    SwitchState::Enabled => cursor_config.hover,
-                    SwitchState::Disabled => cursor_config.disabled,
-                };
-            }
-            CursorInteraction::None => {
-                outline.visible = false;
-            }
+    for (e, switch, cursor_interaction, mut interactable) in query.iter_mut() {
+        // disabled switches never get clicked
+        if let CursorInteraction::Clicked = cursor_interaction {
+            // Play sound
+            let handle = audio_assets.flip.clone();
+            emitter.play_from(e);
+            let instance = channel
+                .play(handle.clone())
+                .with_volume(mixer.volume::<SwitchAudioChannel>())
+                .handle();
+            caption_events.send(PlayCaption::new::<SwitchAudioChannel>(handle, instance));
+
+            interactable.start_cooldown(Duration::from_secs_f32(1.0));
+
+            // send event to target
+            switch_events.send(SwitchEvent(switch.target));
         }
     }
 }
@@ -0,0 +1,183 @@
+use bevy::prelude::*;
+use iyes_loopless::prelude::*;
+use sly_physics::prelude::*;
+
+use crate::{
+    actions::{Action, ActionState},
+    camera::{CameraMain, CameraState},
+    states::PauseState,
+    GameState,
+};
+
+pub struct TractorBeamPlugin;
+
+impl Plugin for TractorBeamPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TractorBeamConfig>()
+            .init_resource::<TractorBeam>()
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                grab_toggle
+                    .run_in_state(GameState::Playing)
+                    .run_in_state(PauseState::Running)
+                    .run_in_state(CameraState::Player),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                hold_object
+                    .run_in_state(GameState::Playing)
+                    .run_in_state(PauseState::Running)
+                    .after(grab_toggle),
+            )
+            .add_exit_system(GameState::Playing, reset_beam)
+            .add_exit_system(CameraState::Player, release_object);
+    }
+}
+
+// Marker for rigid bodies the tractor beam can pick up
+#[derive(Component, Default)]
+pub struct Grabbable;
+
+// Added to whatever the tractor beam is currently holding
+#[derive(Component)]
+pub struct Held;
+
+pub struct TractorBeamConfig {
+    // max distance we can grab something from
+    pub range: f32,
+    // distance in front of the camera we hold things at
+    pub hold_distance: f32,
+    // how hard the spring pulls toward the hold point
+    pub stiffness: f32,
+    // 0-1, how much of the old velocity is kept each frame
+    pub damping: f32,
+    pub max_speed: f32,
+    // drop the object if it gets stuck this far from the hold point
+    pub break_distance: f32,
+}
+
+impl Default for TractorBeamConfig {
+    fn default() -> Self {
+        Self {
+            range: 4.0,
+            hold_distance: 1.5,
+            stiffness: 15.0,
+            damping: 0.2,
+            max_speed: 20.0,
+            break_distance: 3.0,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TractorBeam {
+    pub held: Option<Entity>,
+    // hold point last frame, used to work out the momentum we give on release
+    last_target: Option<Vec3>,
+    target_velocity: Vec3,
+}
+
+fn grab_toggle(
+    mut commands: Commands,
+    actions: Res<ActionState>,
+    camera_query: Query<&Transform, With<CameraMain>>,
+    grabbable_query: Query<(), (With<Grabbable>, With<LinearVelocity>)>,
+    mut velocity_query: Query<&mut LinearVelocity>,
+    tlas: Res<Tlas>,
+    config: Res<TractorBeamConfig>,
+    mut beam: ResMut<TractorBeam>,
+) {
+    if !actions.just_pressed(Action::Grab) {
+        return;
+    }
+
+    // release
+    if let Some(held) = beam.held.take() {
+        if let Ok(mut lin_vel) = velocity_query.get_mut(held) {
+            lin_vel.0 = beam.target_velocity;
+            commands.entity(held).remove::<Held>();
+        }
+        beam.last_target = None;
+        return;
+    }
+
+    // grab
+    let camera_trans = camera_query.single();
+    let mut ray = Ray::new(camera_trans.translation, camera_trans.forward());
+    if let Some(hit) = ray.intersect_tlas(&tlas) {
+        if hit.distance <= config.range && grabbable_query.contains(hit.entity) {
+            commands.entity(hit.entity).insert(Held);
+            beam.held = Some(hit.entity);
+            beam.last_target = None;
+            beam.target_velocity = Vec3::ZERO;
+        }
+    }
+}
+
+fn hold_object(
+    mut commands: Commands,
+    camera_query: Query<&Transform, With<CameraMain>>,
+    mut held_query: Query<(&Transform, &mut LinearVelocity), (With<Held>, Without<CameraMain>)>,
+    config: Res<TractorBeamConfig>,
+    mut beam: ResMut<TractorBeam>,
+    time: Res<Time>,
+) {
+    let held = match beam.held {
+        Some(e) => e,
+        None => return,
+    };
+
+    // held entity was despawned, pellets get cleared on reset
+    let (trans, mut lin_vel) = match held_query.get_mut(held) {
+        Ok(result) => result,
+        Err(_) => {
+            beam.held = None;
+            beam.last_target = None;
+            return;
+        }
+    };
+
+    let camera_trans = camera_query.single();
+    let target = camera_trans.translation + camera_trans.forward() * config.hold_distance;
+
+    let dt = time.delta_seconds();
+    if let Some(last_target) = beam.last_target {
+        if dt > 0.0 {
+            beam.target_velocity = (target - last_target) / dt;
+        }
+    }
+    beam.last_target = Some(target);
+
+    let offset = target - trans.translation;
+    if offset.length() > config.break_distance {
+        // stuck behind something, let it go
+        commands.entity(held).remove::<Held>();
+        beam.held = None;
+        beam.last_target = None;
+        return;
+    }
+
+    // spring toward hold point, following the camera, the physics step still handles collisions
+    let desired =
+        (beam.target_velocity + offset * config.stiffness).clamp_length_max(config.max_speed);
+    let damping = config.damping.clamp(0.0, 1.0);
+    lin_vel.0 = lin_vel.0 * damping + desired * (1.0 - damping);
+}
+
+fn release_object(
+    mut commands: Commands,
+    mut beam: ResMut<TractorBeam>,
+    mut velocity_query: Query<&mut LinearVelocity, With<Held>>,
+) {
+    if let Some(held) = beam.held.take() {
+        if let Ok(mut lin_vel) = velocity_query.get_mut(held) {
+            lin_vel.0 = beam.target_velocity;
+            commands.entity(held).remove::<Held>();
+        }
+    }
+    beam.last_target = None;
+}
+
+fn reset_beam(mut beam: ResMut<TractorBeam>) {
+    *beam = TractorBeam::default();
+}
@@ -0,0 +1,25 @@
+// tlas rays that carry on through things that shouldn't block them, held props, pellets, the ai
+use bevy::prelude::*;
+use sly_physics::prelude::*;
+
+// how many skipped entities a single ray will pass through
+const MAX_PASS_THROUGH: usize = 4;
+
+// distance to the first thing the ray hits that isn't skipped
+pub fn cast_through(
+    tlas: &Tlas,
+    origin: Vec3,
+    direction: Vec3,
+    skip: impl Fn(Entity) -> bool,
+) -> Option<f32> {
+    let mut travelled = 0.0;
+    for _ in 0..MAX_PASS_THROUGH {
+        let mut ray = Ray::new(origin + direction * travelled, direction);
+        let hit = ray.intersect_tlas(tlas)?;
+        if !skip(hit.entity) {
+            return Some(travelled + hit.distance);
+        }
+        travelled += hit.distance + 0.01;
+    }
+    None
+}
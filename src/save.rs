@@ -0,0 +1,247 @@
+// campaign progress, one file per slot in saves/ next to the game
+// every save records the version that wrote it, older ones are migrated forward on load
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use iyes_loopless::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    prefabs::{PolarityBlaster, RoundEvent},
+    states::HighScore,
+    GameState, LevelState,
+};
+
+pub const SAVE_SLOTS: usize = 3;
+
+#[cfg(not(target_arch = "wasm32"))]
+const SAVE_DIR: &str = "saves";
+
+// one entry per format change, the first takes a version 1 save to version 2 and so on
+// saves use serde(default), so only add one when old data needs fixing up, not for new fields
+const MIGRATIONS: &[fn(&mut SaveData)] = &[tutorial_tools];
+
+pub const SAVE_VERSION: u32 = MIGRATIONS.len() as u32 + 1;
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SaveSlots::load())
+            .add_system(record_level.run_in_state(GameState::Playing))
+            .add_system(record_tools.run_in_state(GameState::Playing))
+            .add_system(record_scores.run_in_state(GameState::Playing));
+    }
+}
+
+// things that get unlocked along the way and stay unlocked
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Tool {
+    Blaster,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SaveData {
+    // a file without one predates versioning, so it's the first version
+    #[serde(default = "first_version")]
+    pub version: u32,
+    pub tutorial_complete: bool,
+    // where continue picks up from
+    pub level: LevelState,
+    pub unlocked_levels: Vec<LevelState>,
+    pub best_scores: HashMap<LevelState, u32>,
+    pub tools: Vec<Tool>,
+}
+
+impl Default for SaveData {
+    fn default() -> Self {
+        Self {
+            version: SAVE_VERSION,
+            tutorial_complete: false,
+            level: LevelState::Intro,
+            unlocked_levels: vec![LevelState::Intro],
+            best_scores: HashMap::new(),
+            tools: Vec::new(),
+        }
+    }
+}
+
+fn first_version() -> u32 {
+    1
+}
+
+impl SaveData {
+    pub fn has_tool(&self, tool: Tool) -> bool {
+        self.tools.contains(&tool)
+    }
+
+    pub fn best_score(&self, level: &LevelState) -> u32 {
+        self.best_scores.get(level).copied().unwrap_or_default()
+    }
+
+    fn migrate(&mut self) {
+        let from = self.version.max(1) as usize - 1;
+        for migration in MIGRATIONS.iter().skip(from) {
+            migration(self);
+        }
+        self.version = SAVE_VERSION;
+    }
+}
+
+// version 2: the tutorial skip in intro.rs only looks at tools, so a finished tutorial
+// has to come with the blaster it hands out
+fn tutorial_tools(save: &mut SaveData) {
+    if save.tutorial_complete && !save.has_tool(Tool::Blaster) {
+        save.tools.push(Tool::Blaster);
+    }
+}
+
+pub struct SaveSlots {
+    pub slots: [Option<SaveData>; SAVE_SLOTS],
+    // slot the menu and the running game use
+    pub active: usize,
+}
+
+impl SaveSlots {
+    pub fn current(&self) -> Option<&SaveData> {
+        self.slots[self.active].as_ref()
+    }
+
+    // starts an empty game in the active slot if there isn't one
+    pub fn current_mut(&mut self) -> &mut SaveData {
+        self.slots[self.active].get_or_insert_with(SaveData::default)
+    }
+
+    pub fn new_game(&mut self) {
+        self.slots[self.active] = Some(SaveData::default());
+        self.save();
+    }
+
+    pub fn next_slot(&mut self) {
+        self.active = (self.active + 1) % SAVE_SLOTS;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn path(slot: usize) -> std::path::PathBuf {
+        std::path::Path::new(SAVE_DIR).join(format!("slot_{}.ron", slot + 1))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load() -> Self {
+        let mut slots: [Option<SaveData>; SAVE_SLOTS] = Default::default();
+        let mut active = 0;
+        let mut newest = None;
+        for (i, slot) in slots.iter_mut().enumerate() {
+            let path = Self::path(i);
+            let text = match std::fs::read_to_string(&path) {
+                Ok(text) => text,
+                Err(_) => continue,
+            };
+            let mut save = match ron::from_str::<SaveData>(&text) {
+                Ok(save) => save,
+                Err(e) => {
+                    warn!("failed to parse {:?}: {}", path, e);
+                    continue;
+                }
+            };
+            if save.version > SAVE_VERSION {
+                warn!(
+                    "{:?} is from a newer version ({} > {}), ignoring it",
+                    path, save.version, SAVE_VERSION
+                );
+                continue;
+            }
+            save.migrate();
+            *slot = Some(save);
+
+            // pick up where the player last left off
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+            if modified > newest {
+                newest = modified;
+                active = i;
+            }
+        }
+        Self { slots, active }
+    }
+
+    // writes the active slot
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&self) {
+        let save = match self.current() {
+            Some(save) => save,
+            None => return,
+        };
+        let path = Self::path(self.active);
+        let pretty = ron::ser::PrettyConfig::default();
+        match ron::ser::to_string_pretty(save, pretty) {
+            Ok(text) => {
+                let written =
+                    std::fs::create_dir_all(SAVE_DIR).and_then(|_| std::fs::write(&path, text));
+                if let Err(e) = written {
+                    warn!("failed to write {:?}: {}", path, e);
+                }
+            }
+            Err(e) => warn!("failed to serialize save: {}", e),
+        }
+    }
+
+    // no file system on the web, progress only lasts the session
+    #[cfg(target_arch = "wasm32")]
+    pub fn load() -> Self {
+        Self {
+            slots: Default::default(),
+            active: 0,
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn save(&self) {}
+}
+
+fn record_level(
+    level: Res<CurrentState<LevelState>>,
+    mut saves: ResMut<SaveSlots>,
+    mut high_score: ResMut<HighScore>,
+) {
+    if !level.is_changed() || level.0 == LevelState::None {
+        return;
+    }
+
+    let save = saves.current_mut();
+    save.level = level.0.clone();
+    if !save.unlocked_levels.contains(&level.0) {
+        save.unlocked_levels.push(level.0.clone());
+    }
+    high_score.0 = save.best_score(&level.0);
+    saves.save();
+}
+
+fn record_tools(
+    mut saves: ResMut<SaveSlots>,
+    query: Query<&PolarityBlaster, Changed<PolarityBlaster>>,
+) {
+    let unlocked = query
+        .iter()
+        .any(|blaster| matches!(blaster, PolarityBlaster::Enabled));
+    if unlocked && !matches!(saves.current(), Some(save) if save.has_tool(Tool::Blaster)) {
+        saves.current_mut().tools.push(Tool::Blaster);
+        saves.save();
+    }
+}
+
+fn record_scores(
+    mut saves: ResMut<SaveSlots>,
+    mut round_events: EventReader<RoundEvent>,
+    level: Res<CurrentState<LevelState>>,
+) {
+    for event in round_events.iter() {
+        if let RoundEvent::Finished { score } = event {
+            let save = saves.current_mut();
+            if *score > save.best_score(&level.0) {
+                save.best_scores.insert(level.0.clone(), *score);
+                saves.save();
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    actions::InputMap, graphics::GraphicsSettings, locale::Language, mixer::MixerSettings,
+    subtitles::SubtitleSettings,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+const SETTINGS_PATH: &str = "settings.ron";
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Settings::load());
+    }
+}
+
+// Everything the player can change, saved to settings.ron next to the game
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct Settings {
+    pub language: Language,
+    pub input: InputMap,
+    pub subtitles: SubtitleSettings,
+    pub audio: MixerSettings,
+    pub graphics: GraphicsSettings,
+}
+
+impl Settings {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load() -> Self {
+        match std::fs::read_to_string(SETTINGS_PATH) {
+            Ok(text) => match ron::from_str::<Settings>(&text) {
+                Ok(mut settings) => {
+                    settings.input.add_missing_defaults();
+                    settings
+                }
+                Err(e) => {
+                    warn!("failed to parse {}: {}, using defaults", SETTINGS_PATH, e);
+                    Settings::default()
+                }
+            },
+            Err(_) => Settings::default(),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&self) {
+        let pretty = ron::ser::PrettyConfig::default();
+        match ron::ser::to_string_pretty(self, pretty) {
+            Ok(text) => {
+                if let Err(e) = std::fs::write(SETTINGS_PATH, text) {
+                    warn!("failed to write {}: {}", SETTINGS_PATH, e);
+                }
+            }
+            Err(e) => warn!("failed to serialize settings: {}", e),
+        }
+    }
+
+    // no file system on the web, settings only last the session
+    #[cfg(target_arch = "wasm32")]
+    pub fn load() -> Self {
+        Settings::default()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn save(&self) {}
+}
@@ -0,0 +1,213 @@
+// sounds that come from somewhere in the world, panned and faded by where they are from the camera
+// kira pans whole channels, so a channel follows one emitter at a time, whichever played last
+use std::marker::PhantomData;
+
+use bevy::{prelude::*, transform::TransformSystem};
+use sly_physics::prelude::*;
+
+use crate::{camera::CameraMain, mixer::MixerSystem, prefabs::Pellet, raycast::cast_through};
+
+pub struct SpatialAudioPlugin;
+
+impl Plugin for SpatialAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpatialAudioConfig>();
+    }
+}
+
+pub struct SpatialAudioConfig {
+    // volume left over with a wall between the emitter and the camera
+    pub occluded_volume: f32,
+    // how far off centre a sound can pan, 0.5 is all the way
+    pub pan_width: f32,
+    // per second, so walls and turning don't make the sound jump
+    pub smoothing: f32,
+}
+
+impl Default for SpatialAudioConfig {
+    fn default() -> Self {
+        Self {
+            occluded_volume: 0.35,
+            pan_width: 0.4,
+            smoothing: 8.0,
+        }
+    }
+}
+
+#[derive(Component, Clone)]
+pub struct AudioEmitter {
+    // full volume inside this distance
+    pub min_distance: f32,
+    // silent past this
+    pub max_distance: f32,
+    // muffled by walls, checked with a ray to the camera
+    pub occlude: bool,
+}
+
+impl Default for AudioEmitter {
+    fn default() -> Self {
+        Self {
+            min_distance: 2.0,
+            max_distance: 25.0,
+            occlude: true,
+        }
+    }
+}
+
+impl AudioEmitter {
+    pub fn new(min_distance: f32, max_distance: f32) -> Self {
+        Self {
+            min_distance,
+            max_distance,
+            ..default()
+        }
+    }
+
+    pub fn with_occlusion(mut self, occlude: bool) -> Self {
+        self.occlude = occlude;
+        self
+    }
+
+    fn attenuation(&self, distance: f32) -> f32 {
+        let range = (self.max_distance - self.min_distance).max(1e-3);
+        let t = ((distance - self.min_distance) / range).clamp(0.0, 1.0);
+        (1.0 - t) * (1.0 - t)
+    }
+}
+
+// the emitter a channel is following and what that works out to, the mixer applies it
+pub struct ChannelEmitter<T> {
+    source: Option<Entity>,
+    volume: f32,
+    panning: f32,
+    // new emitter, jump straight to it instead of sliding over from the last one
+    snap: bool,
+    // bumped for every sound, so the mixer sets the volume on it even if nothing else changed
+    plays: u32,
+    marker: PhantomData<T>,
+}
+
+impl<T> Default for ChannelEmitter<T> {
+    fn default() -> Self {
+        Self {
+            source: None,
+            volume: 1.0,
+            panning: 0.5,
+            snap: false,
+            plays: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> ChannelEmitter<T> {
+    // call alongside channel.play for a sound coming from an entity
+    pub fn play_from(&mut self, entity: Entity) {
+        if self.source != Some(entity) {
+            self.source = Some(entity);
+            self.snap = true;
+        }
+        self.plays = self.plays.wrapping_add(1);
+    }
+
+    pub fn plays(&self) -> u32 {
+        self.plays
+    }
+
+    pub fn volume(&self) -> f32 {
+        self.volume
+    }
+
+    // 0 left, 0.5 centre, 1 right
+    pub fn panning(&self) -> f64 {
+        self.panning as f64
+    }
+}
+
+pub trait SpatialAudioApp {
+    // channel volume and panning follow its emitter, use with add_mixer_channel
+    fn add_spatial_channel<T: Resource>(&mut self) -> &mut Self;
+}
+
+impl SpatialAudioApp for App {
+    fn add_spatial_channel<T: Resource>(&mut self) -> &mut Self {
+        self.init_resource::<ChannelEmitter<T>>()
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                update_emitter::<T>
+                    .after(TransformSystem::TransformPropagate)
+                    .before(MixerSystem),
+            )
+    }
+}
+
+fn update_emitter<T: Resource>(
+    mut channel_emitter: ResMut<ChannelEmitter<T>>,
+    emitter_query: Query<(&AudioEmitter, &GlobalTransform)>,
+    camera_query: Query<&GlobalTransform, With<CameraMain>>,
+    ignore_query: Query<(), Or<(With<Pellet>, With<AudioEmitter>)>>,
+    config: Res<SpatialAudioConfig>,
+    tlas: Res<Tlas>,
+    time: Res<Time>,
+) {
+    let source = match channel_emitter.source {
+        Some(source) => source,
+        None => return,
+    };
+    let camera = match camera_query.get_single() {
+        Ok(camera) => camera,
+        Err(_) => return,
+    };
+    let (emitter, emitter_trans) = match emitter_query.get(source) {
+        Ok(emitter) => emitter,
+        // gone, whatever is left plays flat
+        Err(_) => {
+            channel_emitter.source = None;
+            channel_emitter.volume = 1.0;
+            channel_emitter.panning = 0.5;
+            return;
+        }
+    };
+
+    let offset = emitter_trans.translation() - camera.translation();
+    let distance = offset.length();
+    let direction = offset.normalize_or_zero();
+
+    let mut volume = emitter.attenuation(distance);
+    if emitter.occlude
+        && volume > 0.0
+        && is_occluded(
+            &tlas,
+            &ignore_query,
+            camera.translation(),
+            direction,
+            distance,
+        )
+    {
+        volume *= config.occluded_volume;
+    }
+    let panning = 0.5 + camera.right().dot(direction) * config.pan_width;
+
+    if channel_emitter.snap {
+        channel_emitter.volume = volume;
+        channel_emitter.panning = panning;
+        channel_emitter.snap = false;
+    } else {
+        let blend = (config.smoothing * time.delta_seconds()).min(1.0);
+        channel_emitter.volume += (volume - channel_emitter.volume) * blend;
+        channel_emitter.panning += (panning - channel_emitter.panning) * blend;
+    }
+}
+
+// is there a wall between the camera and the emitter
+fn is_occluded(
+    tlas: &Tlas,
+    ignore: &Query<(), Or<(With<Pellet>, With<AudioEmitter>)>>,
+    origin: Vec3,
+    direction: Vec3,
+    distance: f32,
+) -> bool {
+    // pellets and other emitters don't muffle anything
+    cast_through(tlas, origin, direction, |e| ignore.contains(e))
+        .map_or(false, |hit| hit < distance)
+}
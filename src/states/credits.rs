@@ -0,0 +1,261 @@
+// scrolling credits, from the menu or after the last level
+// the text is assets/credits/game.credits.md, build/credits.sh puts it together from CREDIT.md
+// and credits/
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use iyes_loopless::prelude::*;
+
+use crate::{
+    actions::{Action, ActionState},
+    assets::{CreditAssets, FontAssets, CLEAR},
+    cleanup,
+    cursor_lock::ReleaseCursor,
+    GameState, LevelState,
+};
+
+pub struct CreditsPlugin;
+
+impl Plugin for CreditsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<Credits>()
+            .init_asset_loader::<CreditsLoader>()
+            .init_resource::<CreditsConfig>()
+            .add_enter_system(GameState::Credits, setup_credits)
+            .add_system(scroll_credits.run_in_state(GameState::Credits))
+            .add_system(skip_credits.run_in_state(GameState::Credits))
+            .add_exit_system(GameState::Credits, cleanup)
+            .add_enter_system(LevelState::End, roll_credits);
+    }
+}
+
+pub struct CreditsConfig {
+    // pixels per second
+    pub speed: f32,
+    // gap before the first line comes up from the bottom
+    pub delay: f32,
+}
+
+impl Default for CreditsConfig {
+    fn default() -> Self {
+        Self {
+            speed: 40.0,
+            delay: 1.0,
+        }
+    }
+}
+
+#[derive(TypeUuid)]
+#[uuid = "2f6a9c1e-4b3d-4e8f-a7c2-9d1e0b5f3a6c"]
+pub struct Credits {
+    pub lines: Vec<CreditLine>,
+}
+
+pub enum CreditLine {
+    // # in the markdown
+    Heading(String),
+    // ## and deeper
+    SubHeading(String),
+    // list items, who made what
+    Entry(String),
+    // plain text, mostly the licences themselves
+    Licence(String),
+    Blank,
+}
+
+impl Credits {
+    fn parse(text: &str) -> Self {
+        let mut lines = Vec::new();
+        for line in text.lines() {
+            let line = line.trim();
+            let parsed = if line.is_empty() {
+                // one gap is enough
+                if matches!(lines.last(), None | Some(CreditLine::Blank)) {
+                    continue;
+                }
+                CreditLine::Blank
+            } else if line.starts_with("<!--") {
+                continue;
+            } else if let Some(heading) = line.strip_prefix("# ") {
+                CreditLine::Heading(strip_links(heading))
+            } else if line.starts_with("##") {
+                CreditLine::SubHeading(strip_links(line.trim_start_matches('#').trim()))
+            } else if let Some(entry) = line.strip_prefix("* ").or_else(|| line.strip_prefix("- "))
+            {
+                CreditLine::Entry(strip_links(entry))
+            } else {
+                CreditLine::Licence(strip_links(line))
+            };
+            lines.push(parsed);
+        }
+        Self { lines }
+    }
+}
+
+// [text](url) to just the text, the links go nowhere in game
+fn strip_links(line: &str) -> String {
+    let mut out = String::new();
+    let mut rest = line;
+    while let Some(start) = rest.find('[') {
+        let link = rest[start..]
+            .find("](")
+            .and_then(|mid| rest[start + mid..].find(')').map(|end| (mid, mid + end)));
+        match link {
+            Some((mid, end)) => {
+                out.push_str(&rest[..start]);
+                out.push_str(&rest[start + 1..start + mid]);
+                rest = &rest[start + end + 1..];
+            }
+            None => break,
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[derive(Default)]
+pub struct CreditsLoader;
+
+impl AssetLoader for CreditsLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let credits = Credits::parse(std::str::from_utf8(bytes)?);
+            load_context.set_default_asset(LoadedAsset::new(credits));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["credits.md"]
+    }
+}
+
+#[derive(Component)]
+struct CreditsRoll {
+    // top of the roll, from the top of the window
+    offset: f32,
+}
+
+fn setup_credits(
+    mut commands: Commands,
+    font_assets: Res<FontAssets>,
+    credit_assets: Res<CreditAssets>,
+    credits: Res<Assets<Credits>>,
+    windows: Res<Windows>,
+    config: Res<CreditsConfig>,
+    mut clear_color: ResMut<ClearColor>,
+) {
+    clear_color.0 = Color::BLACK;
+
+    let height = windows
+        .get_primary()
+        .map(|w| w.height())
+        .unwrap_or_default();
+    let offset = height + config.delay * config.speed;
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(offset),
+                    ..default()
+                },
+                size: Size::new(Val::Percent(100.0), Val::Auto),
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            color: CLEAR.into(),
+            ..default()
+        })
+        .insert(CreditsRoll { offset })
+        .insert(ReleaseCursor)
+        .insert(Name::new("ui Credits"))
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                style: Style {
+                    margin: UiRect::all(Val::Px(20.0)),
+                    ..default()
+                },
+                text: Text {
+                    sections: vec![font_assets.title("Reactor".to_string(), Color::GOLD)],
+                    alignment: Default::default(),
+                },
+                ..default()
+            });
+
+            let lines = match credits.get(&credit_assets.credits) {
+                Some(credits) => &credits.lines[..],
+                None => &[],
+            };
+            for line in lines {
+                let (section, margin) = match line {
+                    CreditLine::Heading(text) => (font_assets.h1(text.clone(), Color::GOLD), 10.0),
+                    CreditLine::SubHeading(text) => {
+                        (font_assets.sub_title(text.clone(), Color::GOLD), 6.0)
+                    }
+                    CreditLine::Entry(text) => {
+                        (font_assets.sub_title(text.clone(), Color::WHITE), 2.0)
+                    }
+                    CreditLine::Licence(text) => {
+                        (font_assets.sub_title(text.clone(), Color::GRAY), 0.0)
+                    }
+                    CreditLine::Blank => (font_assets.sub_title(String::new(), Color::NONE), 6.0),
+                };
+                parent.spawn_bundle(TextBundle {
+                    style: Style {
+                        margin: UiRect::new(
+                            Val::Px(0.0),
+                            Val::Px(0.0),
+                            Val::Px(margin),
+                            Val::Px(margin),
+                        ),
+                        max_size: Size::new(Val::Percent(80.0), Val::Auto),
+                        ..default()
+                    },
+                    text: Text {
+                        sections: vec![section],
+                        alignment: TextAlignment::CENTER,
+                    },
+                    ..default()
+                });
+            }
+        });
+}
+
+fn scroll_credits(
+    mut commands: Commands,
+    mut roll_query: Query<(&mut CreditsRoll, &mut Style, &Node)>,
+    config: Res<CreditsConfig>,
+    time: Res<Time>,
+) {
+    for (mut roll, mut style, node) in roll_query.iter_mut() {
+        roll.offset -= config.speed * time.delta_seconds();
+        style.position.top = Val::Px(roll.offset);
+
+        // last line has gone off the top
+        if node.size.y > 0.0 && roll.offset < -node.size.y {
+            commands.insert_resource(NextState(GameState::Menu));
+        }
+    }
+}
+
+fn skip_credits(mut commands: Commands, actions: Res<ActionState>) {
+    if actions.just_pressed(Action::Pause) || actions.just_pressed(Action::SkipCredits) {
+        commands.insert_resource(NextState(GameState::Menu));
+    }
+}
+
+// finished the game
+fn roll_credits(mut commands: Commands) {
+    commands.insert_resource(NextState(GameState::Credits));
+    commands.insert_resource(NextState(LevelState::None));
+}
@@ -1,7 +1,15 @@
-use std::time::Duration;
-
-use crate::{assets::*, cleanup, prefabs::*, GameState};
-use bevy::prelude::*;
+use crate::{
+    assets::*,
+    cleanup,
+    locale::{LocaleKey, Strings},
+    music::MusicTrack,
+    prefabs::*,
+    widgets::{ButtonSize, Theme},
+    GameState,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::app::AppExit;
+use bevy::{asset::LoadState, prelude::*};
 use bevy_asset_loader::prelude::*;
 use iyes_loopless::prelude::*;
 
@@ -14,72 +22,342 @@ impl Plugin for LoadingPlugin {
                 LoadingState::new(GameState::Loading)
                     .with_collection::<AudioAssets>()
                     .with_collection::<TextureAssets>()
-                    .with_collection::<AIAudioAssets>()
+                    .with_collection::<DialogueAssets>()
+                    .with_collection::<CaptionAssets>()
+                    .with_collection::<CreditAssets>()
                     .with_collection::<SwitchAudioAssets>()
                     .with_collection::<SpaceKitAssets>()
                     .continue_to_state(GameState::Menu),
             )
+            // exclusive, so it runs before setup and the progress is there to build the ui from
+            .add_enter_system(GameState::Loading, track_collections.exclusive_system())
             .add_enter_system(GameState::Loading, setup)
-            .add_stage_before(
-                CoreStage::Update,
-                "loading_update",
-                FixedTimestepStage::new(Duration::from_secs_f64(0.5))
-                    .with_stage(SystemStage::parallel().with_system(update_text)),
+            .add_system(track_dependencies.run_in_state(GameState::Loading))
+            .add_system(
+                update_progress
+                    .run_in_state(GameState::Loading)
+                    .after(track_dependencies),
             )
-            .add_exit_system(GameState::Loading, cleanup);
+            .add_exit_system(GameState::Loading, cleanup)
+            .add_exit_system(GameState::Loading, stop_tracking)
+            .add_enter_system(GameState::LoadFailed, setup_failed)
+            .add_system(click_failed_button.run_in_state(GameState::LoadFailed))
+            .add_exit_system(GameState::LoadFailed, cleanup)
+            .add_exit_system(GameState::LoadFailed, clear_failure);
+    }
+}
+
+// same handles the loading state is waiting on, the asset server hands back the ones it already has
+struct LoadingProgress {
+    collections: Vec<Collection>,
+}
+
+// path of the asset that stopped loading
+struct LoadFailure(String);
+
+struct Collection {
+    // string table key
+    name: &'static str,
+    handles: Vec<HandleUntyped>,
+    // voice lines and music stems the collection's assets load, added once those are in
+    dependencies_tracked: bool,
+}
+
+impl Collection {
+    fn new<T: AssetCollection>(world: &mut World, name: &'static str) -> Self {
+        Self {
+            name,
+            handles: T::load(world),
+            dependencies_tracked: false,
+        }
+    }
+}
+
+#[derive(Component)]
+struct LoadingBar;
+
+#[derive(Component, Copy, Clone)]
+enum FailedButton {
+    Retry,
+    #[cfg(not(target_arch = "wasm32"))]
+    Quit,
+}
+
+impl LocaleKey for FailedButton {
+    fn key(&self) -> &'static str {
+        match self {
+            FailedButton::Retry => "loading.retry",
+            #[cfg(not(target_arch = "wasm32"))]
+            FailedButton::Quit => "loading.quit",
+        }
     }
 }
 
+impl FailedButton {
+    fn iter() -> impl Iterator<Item = Self> {
+        [
+            FailedButton::Retry,
+            #[cfg(not(target_arch = "wasm32"))]
+            FailedButton::Quit,
+        ]
+        .into_iter()
+    }
+}
+
+// one line per collection
 #[derive(Component)]
-struct LoadingText;
+struct CollectionText(usize);
+
+fn track_collections(world: &mut World) {
+    let collections = vec![
+        Collection::new::<AudioAssets>(world, "loading.audio"),
+        Collection::new::<TextureAssets>(world, "loading.textures"),
+        Collection::new::<DialogueAssets>(world, "loading.dialogue"),
+        Collection::new::<CaptionAssets>(world, "loading.captions"),
+        Collection::new::<CreditAssets>(world, "loading.credits"),
+        Collection::new::<SwitchAudioAssets>(world, "loading.switch_audio"),
+        Collection::new::<SpaceKitAssets>(world, "loading.space_kit"),
+    ];
+    world.insert_resource(LoadingProgress { collections });
+}
+
+// the loading state only waits on the collections, not what their assets pull in with them
+fn track_dependencies(
+    mut progress: ResMut<LoadingProgress>,
+    asset_server: Res<AssetServer>,
+    dialogue_scripts: Res<Assets<DialogueScript>>,
+    bark_sets: Res<Assets<BarkSet>>,
+    music_tracks: Res<Assets<MusicTrack>>,
+) {
+    for collection in progress.collections.iter_mut() {
+        if collection.dependencies_tracked {
+            continue;
+        }
+        let loaded = collection
+            .handles
+            .iter()
+            .all(|handle| asset_server.get_load_state(handle) == LoadState::Loaded);
+        if !loaded {
+            continue;
+        }
+
+        let mut paths = collection
+            .handles
+            .iter()
+            .flat_map(|handle| {
+                let dialogue = dialogue_scripts.get(handle).into_iter();
+                let barks = bark_sets.get(handle).into_iter();
+                let music = music_tracks.get(handle).into_iter();
+                dialogue
+                    .flat_map(|script| script.audio())
+                    .chain(barks.flat_map(|set| set.audio()))
+                    .chain(music.flat_map(|track| track.audio()))
+            })
+            .collect::<Vec<_>>();
+        // the same line can be in more than one pool
+        paths.sort_unstable();
+        paths.dedup();
+
+        let dependencies = paths
+            .into_iter()
+            .map(|path| asset_server.get_handle_untyped(path))
+            .collect::<Vec<_>>();
+        collection.handles.extend(dependencies);
+        collection.dependencies_tracked = true;
+    }
+}
+
+fn stop_tracking(mut commands: Commands) {
+    commands.remove_resource::<LoadingProgress>();
+}
 
 fn setup(
     mut commands: Commands,
-    font_assets: Res<FontAssets>,
+    theme: Theme,
+    progress: Res<LoadingProgress>,
     mut clear_color: ResMut<ClearColor>,
 ) {
     clear_color.0 = Color::BLACK;
 
     commands
-        .spawn_bundle(TextBundle {
-            style: Style {
-                position_type: PositionType::Absolute,
-                position: UiRect::<Val> {
-                    left: Val::Percent(85.0),
-                    bottom: Val::Percent(15.0),
-                    ..Default::default()
-                },
-                align_self: AlignSelf::FlexStart,
+        .spawn_bundle(theme.panel(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect::<Val> {
+                left: Val::Percent(60.0),
+                bottom: Val::Percent(10.0),
                 ..Default::default()
             },
-            // Use `Text` directly
-            text: Text {
-                alignment: TextAlignment {
-                    vertical: VerticalAlign::Center,
-                    horizontal: HorizontalAlign::Left,
-                },
-                // Construct a `Vec` of `TextSection`s
-                sections: vec![
-                    font_assets.h1("Loading".into(), Color::WHITE),
-                    font_assets.h1("".into(), Color::WHITE),
-                ],
-            },
+            size: Size::new(Val::Percent(30.0), Val::Auto),
+            flex_direction: FlexDirection::ColumnReverse,
+            align_items: AlignItems::FlexStart,
             ..Default::default()
-        })
+        }))
         .insert(Name::new("ui Loading"))
-        .insert(LoadingText);
+        .with_children(|parent| {
+            parent.spawn_bundle(theme.heading("loading.title", Color::WHITE, default()));
+
+            // bar, filled by update_progress
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(100.0), Val::Px(8.0)),
+                        margin: UiRect::new(Val::Px(0.0), Val::Px(0.0), Val::Px(6.0), Val::Px(6.0)),
+                        ..Default::default()
+                    },
+                    color: Color::rgb(0.15, 0.15, 0.15).into(),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Percent(0.0), Val::Percent(100.0)),
+                                ..Default::default()
+                            },
+                            color: Color::GOLD.into(),
+                            ..Default::default()
+                        })
+                        .insert(LoadingBar);
+                });
+
+            for i in 0..progress.collections.len() {
+                parent
+                    .spawn_bundle(theme.text("", Style::default()))
+                    .insert(CollectionText(i));
+            }
+        });
 }
 
-fn update_text(mut query: Query<&mut Text, With<LoadingText>>, mut count: Local<usize>) {
-    for mut text in query.iter_mut() {
-        // Update the value of the second section
-        let str = match *count {
-            0 => ".",
-            1 => "..",
-            _ => "...",
+fn update_progress(
+    mut commands: Commands,
+    progress: Res<LoadingProgress>,
+    asset_server: Res<AssetServer>,
+    strings: Res<Strings>,
+    mut bar_query: Query<&mut Style, With<LoadingBar>>,
+    mut text_query: Query<(&CollectionText, &mut Text)>,
+) {
+    let mut loaded_total = 0;
+    let mut total = 0;
+    let mut failed = None;
+
+    let counts = progress
+        .collections
+        .iter()
+        .map(|collection| {
+            let mut loaded = 0;
+            for handle in collection.handles.iter() {
+                match asset_server.get_load_state(handle) {
+                    LoadState::Loaded => loaded += 1,
+                    LoadState::Failed if failed.is_none() => {
+                        failed = Some(match asset_server.get_handle_path(handle) {
+                            Some(path) => match path.label() {
+                                Some(label) => format!("{}#{}", path.path().display(), label),
+                                None => path.path().display().to_string(),
+                            },
+                            None => strings.format(
+                                "loading.unknown_asset",
+                                &[("collection", strings.get(collection.name))],
+                            ),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+            loaded_total += loaded;
+            total += collection.handles.len();
+            (loaded, collection.handles.len())
+        })
+        .collect::<Vec<_>>();
+
+    for mut style in bar_query.iter_mut() {
+        let fraction = if total == 0 {
+            1.0
+        } else {
+            loaded_total as f32 / total as f32
         };
-        text.sections[1].value = str.to_string();
-        *count += 1;
-        *count %= 3;
+        style.size.width = Val::Percent(fraction * 100.0);
+    }
+
+    for (CollectionText(i), mut text) in text_query.iter_mut() {
+        if let (Some(collection), Some((loaded, count))) =
+            (progress.collections.get(*i), counts.get(*i))
+        {
+            text.sections[0].value = strings.format(
+                "loading.progress",
+                &[
+                    ("name", strings.get(collection.name)),
+                    ("loaded", loaded.to_string()),
+                    ("total", count.to_string()),
+                ],
+            );
+            text.sections[0].style.color = if loaded == count {
+                Color::WHITE
+            } else {
+                Color::GRAY
+            };
+        }
+    }
+
+    // the loading state would wait on it forever
+    if let Some(path) = failed {
+        error!("failed to load {}", path);
+        commands.insert_resource(LoadFailure(path));
+        commands.insert_resource(NextState(GameState::LoadFailed));
     }
 }
+
+fn setup_failed(mut commands: Commands, theme: Theme, failure: Res<LoadFailure>) {
+    commands
+        .spawn_bundle(theme.panel(Style {
+            size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+            flex_direction: FlexDirection::ColumnReverse,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..Default::default()
+        }))
+        .insert(Name::new("ui Load Failed"))
+        .with_children(|parent| {
+            parent.spawn_bundle(theme.heading(
+                "loading.failed",
+                Color::RED,
+                Style {
+                    margin: UiRect::all(Val::Px(10.0)),
+                    ..Default::default()
+                },
+            ));
+            parent.spawn_bundle(theme.text(failure.0.clone(), Style::default()));
+            let mut hint = theme.label(
+                "loading.failed_hint",
+                Style {
+                    margin: UiRect::all(Val::Px(10.0)),
+                    ..Default::default()
+                },
+            );
+            hint.text.text.sections[0].style.color = Color::GRAY;
+            parent.spawn_bundle(hint);
+            for b in FailedButton::iter() {
+                theme.button(parent, b, ButtonSize::Large);
+            }
+        });
+}
+
+fn click_failed_button(
+    mut commands: Commands,
+    interaction_query: Query<(&Interaction, &FailedButton), (Changed<Interaction>, With<Button>)>,
+    #[cfg(not(target_arch = "wasm32"))] mut app_exit: EventWriter<AppExit>,
+) {
+    for (interaction, btn) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            match btn {
+                // the asset server tries failed paths again, in case the files were put back
+                FailedButton::Retry => commands.insert_resource(NextState(GameState::Loading)),
+                #[cfg(not(target_arch = "wasm32"))]
+                FailedButton::Quit => app_exit.send(AppExit),
+            }
+        }
+    }
+}
+
+fn clear_failure(mut commands: Commands) {
+    commands.remove_resource::<LoadFailure>();
+}
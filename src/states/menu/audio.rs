@@ -1,41 +1,36 @@
-use std::time::Duration;
-
 use bevy::prelude::*;
 use bevy_kira_audio::prelude::*;
 
-use crate::assets::AudioAssets;
+use crate::{
+    assets::AudioAssets,
+    mixer::{fade, Bus, Mixer, MixerChannel},
+};
 
 pub struct MenuBackgroundAudio;
 
+impl MixerChannel for MenuBackgroundAudio {
+    const BUS: Bus = Bus::Music;
+    // paused on leaving the menu instead
+    const IN_GAME: bool = false;
+}
+
 // TODO: look at using instance_control
 // https://github.com/NiklasEi/bevy_kira_audio/blob/main/examples/instance_control.rs
 pub fn start_audio(
     audio_assets: Res<AudioAssets>,
     background: Res<AudioChannel<MenuBackgroundAudio>>,
+    mixer: Res<Mixer>,
     mut started: Local<bool>,
 ) {
     if !*started {
         background
             .play(audio_assets.intro.clone())
             .looped()
-            .with_volume(0.3)
-            .fade_in(AudioTween::new(
-                Duration::from_secs_f32(1.0),
-                AudioEasing::Linear,
-            ));
+            .with_volume(mixer.volume::<MenuBackgroundAudio>())
+            .fade_in(fade(1.0));
 
         *started = true;
     } else {
-        background.resume().fade_in(AudioTween::new(
-            Duration::from_secs_f32(1.0),
-            AudioEasing::Linear,
-        ));
+        background.resume().fade_in(fade(1.0));
     }
 }
-
-pub fn stop_audio(background: Res<AudioChannel<MenuBackgroundAudio>>) {
-    background.pause().fade_out(AudioTween::new(
-        Duration::from_secs_f32(1.0),
-        AudioEasing::Linear,
-    ));
-}
@@ -3,29 +3,55 @@ use bevy::app::AppExit;
 use bevy::prelude::*;
 use iyes_loopless::prelude::*;
 
-use crate::{GameState, LevelState};
+use super::{level_name, MenuPage};
+use crate::{
+    locale::{LocaleKey, Strings, Translate},
+    save::SaveSlots,
+    states::SettingsMenu,
+    GameState, LevelState,
+};
 
-#[derive(Component, Copy, Clone)]
+#[derive(Component, Copy, Clone, PartialEq, Eq)]
 pub enum MenuButton {
+    Continue,
     Play,
+    Levels,
+    Slot,
+    Settings,
+    Credits,
     #[cfg(not(target_arch = "wasm32"))]
     Exit,
 }
 
-impl From<MenuButton> for String {
-    fn from(b: MenuButton) -> Self {
-        match b {
-            MenuButton::Play => "Play".to_string(),
+impl LocaleKey for MenuButton {
+    fn key(&self) -> &'static str {
+        match self {
+            MenuButton::Continue => "menu.continue",
+            MenuButton::Play => "menu.new_game",
+            MenuButton::Levels => "menu.level_select",
+            // slot number and progress filled in by update_menu_buttons
+            MenuButton::Slot => "menu.slot",
+            MenuButton::Settings => "menu.settings",
+            MenuButton::Credits => "menu.credits",
             #[cfg(not(target_arch = "wasm32"))]
-            MenuButton::Exit => "Exit".to_string(),
+            MenuButton::Exit => "menu.exit",
         }
     }
 }
 
+// new game asks again before wiping a slot that has progress in it
+#[derive(Default)]
+pub struct ConfirmNewGame(pub bool);
+
 impl MenuButton {
     pub fn iter() -> impl Iterator<Item = Self> {
         [
+            MenuButton::Continue,
             MenuButton::Play,
+            MenuButton::Levels,
+            MenuButton::Slot,
+            MenuButton::Settings,
+            MenuButton::Credits,
             #[cfg(not(target_arch = "wasm32"))]
             MenuButton::Exit,
         ]
@@ -36,15 +62,38 @@ impl MenuButton {
 pub fn button_click(
     mut commands: Commands,
     interaction_query: Query<(&Interaction, &MenuButton), (Changed<Interaction>, With<Button>)>,
+    mut saves: ResMut<SaveSlots>,
+    mut confirm: ResMut<ConfirmNewGame>,
     #[cfg(not(target_arch = "wasm32"))] mut app_exit: EventWriter<AppExit>,
 ) {
     for (interaction, btn) in interaction_query.iter() {
         if *interaction == Interaction::Clicked {
+            if *btn != MenuButton::Play && confirm.0 {
+                confirm.0 = false;
+            }
             match btn {
+                MenuButton::Continue => {
+                    if let Some(save) = saves.current() {
+                        commands.insert_resource(NextState(GameState::Playing));
+                        commands.insert_resource(NextState(save.level.clone()));
+                    }
+                }
                 MenuButton::Play => {
+                    if saves.current().is_some() && !confirm.0 {
+                        confirm.0 = true;
+                        continue;
+                    }
+                    confirm.0 = false;
+                    saves.new_game();
                     commands.insert_resource(NextState(GameState::Playing));
                     commands.insert_resource(NextState(LevelState::Intro));
                 }
+                MenuButton::Levels => commands.insert_resource(NextState(MenuPage::Levels)),
+                MenuButton::Slot => saves.next_slot(),
+                MenuButton::Settings => {
+                    commands.insert_resource(NextState(SettingsMenu::Open));
+                }
+                MenuButton::Credits => commands.insert_resource(NextState(GameState::Credits)),
 
                 #[cfg(not(target_arch = "wasm32"))]
                 MenuButton::Exit => app_exit.send(AppExit),
@@ -52,3 +101,61 @@ pub fn button_click(
         }
     }
 }
+
+// slot name and progress, new game asking to confirm, continue only shows when there's something to continue
+#[allow(clippy::type_complexity)]
+pub fn update_menu_buttons(
+    saves: Res<SaveSlots>,
+    confirm: Res<ConfirmNewGame>,
+    strings: Res<Strings>,
+    mut button_query: Query<(&MenuButton, &Children, &mut Style, &mut Visibility)>,
+    mut translate_query: Query<&mut Translate>,
+    added_query: Query<(), Added<MenuButton>>,
+) {
+    if !saves.is_changed()
+        && !confirm.is_changed()
+        && !strings.is_changed()
+        && added_query.is_empty()
+    {
+        return;
+    }
+
+    for (btn, children, mut style, mut visibility) in button_query.iter_mut() {
+        match btn {
+            MenuButton::Continue => {
+                let show = saves.current().is_some();
+                visibility.is_visible = show;
+                style.display = if show { Display::Flex } else { Display::None };
+            }
+            MenuButton::Play => {
+                let key = if confirm.0 {
+                    "menu.new_game_confirm"
+                } else {
+                    MenuButton::Play.key()
+                };
+                for child in children.iter() {
+                    if let Ok(mut translate) = translate_query.get_mut(*child) {
+                        *translate = Translate::new(key).with_arg("slot", saves.active + 1);
+                    }
+                }
+            }
+            MenuButton::Slot => {
+                let progress = match saves.current() {
+                    Some(save) => strings.get(level_name(&save.level)),
+                    None => strings.get("menu.slot_empty"),
+                };
+                for child in children.iter() {
+                    if let Ok(mut translate) = translate_query.get_mut(*child) {
+                        translate.set_arg("slot", saves.active + 1);
+                        translate.set_arg("progress", &progress);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn reset_confirm(mut confirm: ResMut<ConfirmNewGame>) {
+    confirm.0 = false;
+}
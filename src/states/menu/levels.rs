@@ -0,0 +1,194 @@
+// level select page, every level with whether it's unlocked in the active save and the best score
+use bevy::prelude::*;
+use iyes_loopless::prelude::*;
+
+use crate::{
+    actions::{Action, ActionState},
+    locale::LocaleKey,
+    save::SaveSlots,
+    widgets::{ButtonSize, Theme},
+    GameState, LevelState,
+};
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub enum MenuPage {
+    Main,
+    Levels,
+}
+
+// in the order they're played, add new levels here as they get built
+const LEVELS: [LevelInfo; 1] = [LevelInfo {
+    level: LevelState::Intro,
+    name: "level.intro.name",
+    description: "level.intro.description",
+}];
+
+// name and description are string table keys
+struct LevelInfo {
+    level: LevelState,
+    name: &'static str,
+    description: &'static str,
+}
+
+// key for the level's name, for showing progress
+pub fn level_name(level: &LevelState) -> &'static str {
+    LEVELS
+        .iter()
+        .find(|info| info.level == *level)
+        .map(|info| info.name)
+        .unwrap_or("level.unknown")
+}
+
+// main menu buttons, hidden while the level list is up
+#[derive(Component)]
+pub struct MainMenuButtons;
+
+#[derive(Component)]
+pub struct LevelSelect;
+
+#[derive(Component, Copy, Clone)]
+pub enum LevelButton {
+    // index into LEVELS
+    Level(usize),
+    Back,
+}
+
+impl LocaleKey for LevelButton {
+    fn key(&self) -> &'static str {
+        match *self {
+            LevelButton::Level(i) => LEVELS[i].name,
+            LevelButton::Back => "menu.back",
+        }
+    }
+}
+
+pub fn setup_levels(mut commands: Commands, theme: Theme, saves: Res<SaveSlots>) {
+    let save = saves.current();
+
+    commands
+        .spawn_bundle(theme.panel(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Percent(35.0),
+                left: Val::Percent(20.0),
+                ..default()
+            },
+            size: Size::new(Val::Percent(60.0), Val::Percent(55.0)),
+            flex_direction: FlexDirection::ColumnReverse,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::FlexStart,
+            ..default()
+        }))
+        .insert(LevelSelect)
+        .insert(Name::new("ui Level Select"))
+        .with_children(|parent| {
+            for (i, info) in LEVELS.iter().enumerate() {
+                // debug builds can jump anywhere without playing through
+                let unlocked = cfg!(debug_assertions)
+                    || info.level == LevelState::Intro
+                    || matches!(save, Some(save) if save.unlocked_levels.contains(&info.level));
+                let best = save
+                    .map(|save| save.best_score(&info.level))
+                    .unwrap_or_default();
+
+                parent
+                    .spawn_bundle(theme.panel(Style {
+                        size: Size::new(Val::Percent(100.0), Val::Auto),
+                        margin: UiRect::all(Val::Px(6.0)),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    }))
+                    .with_children(|parent| {
+                        if unlocked {
+                            theme.button(parent, LevelButton::Level(i), ButtonSize::Large);
+                        } else {
+                            let mut text = theme
+                                .button_text(theme.strings.get("level.locked"), ButtonSize::Large);
+                            text.text.sections[0].style.color = Color::GRAY;
+                            parent
+                                .spawn_bundle(theme.button_bundle(ButtonSize::Large))
+                                .with_children(|parent| {
+                                    parent.spawn_bundle(theme.translated("level.locked", text));
+                                });
+                        }
+
+                        parent
+                            .spawn_bundle(theme.panel(Style {
+                                flex_direction: FlexDirection::ColumnReverse,
+                                margin: UiRect::new(
+                                    Val::Px(12.0),
+                                    Val::Px(0.0),
+                                    Val::Px(0.0),
+                                    Val::Px(0.0),
+                                ),
+                                ..default()
+                            }))
+                            .with_children(|parent| {
+                                let best = if best > 0 {
+                                    best.to_string()
+                                } else {
+                                    "-".to_string()
+                                };
+                                let mut text = theme.label("level.best", default());
+                                text.text.text.sections[0].style.color = Color::GOLD;
+                                text.translate = text.translate.with_arg("score", best);
+                                parent.spawn_bundle(text);
+                                let description = if unlocked {
+                                    info.description
+                                } else {
+                                    "level.locked_description"
+                                };
+                                parent.spawn_bundle(theme.label(description, default()));
+                            });
+                    });
+            }
+
+            theme.button(parent, LevelButton::Back, ButtonSize::Large);
+        });
+}
+
+pub fn despawn_levels(mut commands: Commands, query: Query<Entity, With<LevelSelect>>) {
+    for e in query.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+}
+
+pub fn hide_main_buttons(mut query: Query<&mut Visibility, With<MainMenuButtons>>) {
+    for mut visibility in query.iter_mut() {
+        visibility.is_visible = false;
+    }
+}
+
+pub fn show_main_buttons(mut query: Query<&mut Visibility, With<MainMenuButtons>>) {
+    for mut visibility in query.iter_mut() {
+        visibility.is_visible = true;
+    }
+}
+
+pub fn level_click(
+    mut commands: Commands,
+    interaction_query: Query<(&Interaction, &LevelButton), (Changed<Interaction>, With<Button>)>,
+) {
+    for (interaction, btn) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            match *btn {
+                LevelButton::Level(i) => {
+                    commands.insert_resource(NextState(GameState::Playing));
+                    commands.insert_resource(NextState(LEVELS[i].level.clone()));
+                }
+                LevelButton::Back => commands.insert_resource(NextState(MenuPage::Main)),
+            }
+        }
+    }
+}
+
+pub fn back_hotkey(mut commands: Commands, actions: Res<ActionState>) {
+    if actions.just_pressed(Action::Pause) {
+        commands.insert_resource(NextState(MenuPage::Main));
+    }
+}
+
+// coming back to the menu later starts on the main page
+pub fn reset_page(mut commands: Commands) {
+    commands.insert_resource(NextState(MenuPage::Main));
+}
@@ -1,11 +1,17 @@
 mod audio;
 mod buttons;
+mod levels;
 
 use audio::*;
 use buttons::*;
+pub use levels::MenuPage;
+use levels::*;
 
-use crate::assets::*;
+use crate::actions::{Action, ActionState};
 use crate::cleanup;
+use crate::mixer::{pause_with_fade, MixerApp};
+use crate::states::{HideInSettings, SettingsMenu};
+use crate::widgets::{ButtonSize, Theme};
 use crate::GameState;
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -18,40 +24,61 @@ pub struct MenuPlugin;
 
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_audio_channel::<MenuBackgroundAudio>()
+        app.add_mixer_channel::<MenuBackgroundAudio>()
+            .add_loopless_state(MenuPage::Main)
+            .init_resource::<ConfirmNewGame>()
             .add_enter_system(GameState::Menu, setup_menu)
             .add_enter_system(GameState::Menu, start_audio)
-            .add_system(button_click.run_in_state(GameState::Menu))
-            .add_exit_system(GameState::Menu, stop_audio)
+            .add_system(
+                button_click
+                    .run_in_state(GameState::Menu)
+                    .run_in_state(MenuPage::Main)
+                    .run_in_state(SettingsMenu::Closed),
+            )
+            .add_system(update_menu_buttons.run_in_state(GameState::Menu))
+            .add_enter_system(MenuPage::Levels, setup_levels)
+            .add_enter_system(MenuPage::Levels, hide_main_buttons)
+            .add_system(
+                level_click
+                    .run_in_state(GameState::Menu)
+                    .run_in_state(MenuPage::Levels),
+            )
+            .add_system(
+                back_hotkey
+                    .run_in_state(GameState::Menu)
+                    .run_in_state(MenuPage::Levels),
+            )
+            .add_exit_system(MenuPage::Levels, despawn_levels)
+            .add_exit_system(MenuPage::Levels, show_main_buttons)
+            .add_exit_system(GameState::Menu, pause_with_fade::<MenuBackgroundAudio>)
+            .add_exit_system(GameState::Menu, reset_page)
+            .add_exit_system(GameState::Menu, reset_confirm)
             .add_exit_system(GameState::Menu, cleanup);
 
         #[cfg(not(target_arch = "wasm32"))]
-        app.add_system(exit_window.run_in_state(GameState::Menu));
+        app.add_system(
+            exit_window
+                .run_in_state(GameState::Menu)
+                .run_in_state(MenuPage::Main)
+                .run_in_state(SettingsMenu::Closed),
+        );
     }
 }
 
-fn setup_menu(
-    mut commands: Commands,
-    font_assets: Res<FontAssets>,
-    button_colors: Res<ButtonColors>,
-) {
+fn setup_menu(mut commands: Commands, theme: Theme) {
     // Title Bar
     commands
-        .spawn_bundle(NodeBundle {
-            style: Style {
-                position_type: PositionType::Absolute,
-                size: Size::new(Val::Percent(100.0), Val::Percent(20.0)),
-                position: UiRect {
-                    top: Val::Percent(10.0),
-                    ..Default::default()
-                },
-                flex_direction: FlexDirection::ColumnReverse,
-                justify_content: JustifyContent::Center,
+        .spawn_bundle(theme.panel(Style {
+            position_type: PositionType::Absolute,
+            size: Size::new(Val::Percent(100.0), Val::Percent(20.0)),
+            position: UiRect {
+                top: Val::Percent(10.0),
                 ..Default::default()
             },
-            color: CLEAR.into(),
+            flex_direction: FlexDirection::ColumnReverse,
+            justify_content: JustifyContent::Center,
             ..Default::default()
-        })
+        }))
         .insert(Name::new("Title Bar"))
         .with_children(|parent| {
             parent
@@ -61,7 +88,7 @@ fn setup_menu(
                         ..Default::default()
                     },
                     text: Text {
-                        sections: vec![font_assets.title("Reactor".to_string(), Color::GOLD)],
+                        sections: vec![theme.fonts.title("Reactor".to_string(), Color::GOLD)],
                         alignment: Default::default(),
                     },
                     ..Default::default()
@@ -70,32 +97,21 @@ fn setup_menu(
 
             // author
             parent
-                .spawn_bundle(NodeBundle {
-                    style: Style {
-                        align_self: AlignSelf::Center,
-                        ..Default::default()
-                    },
-                    color: CLEAR.into(),
+                .spawn_bundle(theme.panel(Style {
+                    align_self: AlignSelf::Center,
                     ..Default::default()
-                })
+                }))
                 .insert(Name::new("Author Tag"))
                 .with_children(|parent| {
                     parent
-                        .spawn_bundle(TextBundle {
-                            style: Style {
+                        .spawn_bundle(theme.label(
+                            "menu.author",
+                            Style {
                                 align_self: AlignSelf::Center,
                                 margin: UiRect::all(Val::Px(5.0)),
                                 ..Default::default()
                             },
-                            text: Text {
-                                sections: vec![font_assets.sub_title(
-                                    "by Patrick Towles (Slyedoc)".to_string(),
-                                    Color::WHITE,
-                                )],
-                                alignment: Default::default(),
-                            },
-                            ..default()
-                        })
+                        ))
                         .insert(Name::new("Author"));
                 });
 
@@ -112,72 +128,44 @@ fn setup_menu(
                 .insert(Name::new("Dev Tag"))
                 .with_children(|parent| {
                     parent
-                        .spawn_bundle(TextBundle {
-                            style: Style {
+                        .spawn_bundle(theme.label(
+                            "menu.jam",
+                            Style {
                                 align_self: AlignSelf::Center,
                                 margin: UiRect::all(Val::Px(5.0)),
                                 ..Default::default()
                             },
-                            text: Text {
-                                sections: vec![font_assets
-                                    .sub_title("for Bevy Game Jam #2".to_string(), Color::WHITE)],
-                                alignment: Default::default(),
-                            },
-                            ..default()
-                        })
+                        ))
                         .insert(Name::new("Dev"));
                 });
         });
 
     commands
-        .spawn_bundle(NodeBundle {
-            style: Style {
-                position_type: PositionType::Absolute,
-                position: UiRect {
-                    top: Val::Percent(40.0),
-                    left: Val::Percent(30.0),
-                    ..Default::default()
-                },
-                size: Size::new(Val::Percent(40.0), Val::Percent(40.0)),
-                flex_direction: FlexDirection::ColumnReverse,
-                align_content: AlignContent::Stretch,
-                justify_content: JustifyContent::Center,
+        .spawn_bundle(theme.panel(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Percent(32.0),
+                left: Val::Percent(30.0),
                 ..Default::default()
             },
-            color: CLEAR.into(),
+            size: Size::new(Val::Percent(40.0), Val::Percent(60.0)),
+            flex_direction: FlexDirection::ColumnReverse,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::FlexStart,
             ..Default::default()
-        })
+        }))
+        .insert(HideInSettings)
+        .insert(MainMenuButtons)
         .with_children(|parent| {
             for b in MenuButton::iter() {
-                parent
-                    .spawn_bundle(ButtonBundle {
-                        style: Style {
-                            margin: UiRect::all(Val::Px(10.0)),
-                            align_items: AlignItems::Center,
-                            align_content: AlignContent::Center,
-                            justify_content: JustifyContent::Center,
-                            ..Default::default()
-                        },
-                        color: button_colors.normal,
-                        ..Default::default()
-                    })
-                    .with_children(|parent| {
-                        parent.spawn_bundle(TextBundle {
-                            text: Text {
-                                sections: vec![font_assets.h1(b.into(), Color::rgb(0.9, 0.9, 0.9))],
-                                alignment: Default::default(),
-                            },
-                            ..Default::default()
-                        });
-                    })
-                    .insert(b);
+                theme.button(parent, b, ButtonSize::Large);
             }
         });
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-pub fn exit_window(input: Res<Input<KeyCode>>, mut app_exit: EventWriter<AppExit>) {
-    if input.just_pressed(KeyCode::Escape) {
+pub fn exit_window(actions: Res<ActionState>, mut app_exit: EventWriter<AppExit>) {
+    if actions.just_pressed(Action::Pause) {
         app_exit.send(AppExit);
     }
 }
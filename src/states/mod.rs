@@ -1,13 +1,19 @@
+mod credits;
 mod loading;
 mod menu;
+mod pause;
 mod playing;
 mod pre_loading;
+mod settings_menu;
 
 use bevy::prelude::*;
+pub use credits::*;
 pub use loading::*;
 pub use menu::*;
+pub use pause::*;
 pub use playing::*;
 pub use pre_loading::*;
+pub use settings_menu::*;
 
 pub struct StatePlugin;
 
@@ -15,7 +21,10 @@ impl Plugin for StatePlugin {
     fn build(&self, app: &mut App) {
         app.add_plugin(PreLoadingPlugin)
             .add_plugin(LoadingPlugin)
+            .add_plugin(CreditsPlugin)
             .add_plugin(MenuPlugin)
-            .add_plugin(PlayingPlugin);
+            .add_plugin(PlayingPlugin)
+            .add_plugin(PausePlugin)
+            .add_plugin(SettingsMenuPlugin);
     }
 }
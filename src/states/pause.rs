@@ -0,0 +1,179 @@
+// pause menu over a running level, everything freezes in place until resume
+use bevy::prelude::*;
+use bevy_tweening::{Animator, AnimatorState};
+use iyes_loopless::prelude::*;
+use sly_physics::prelude::*;
+
+use crate::{
+    actions::{Action, ActionState},
+    cursor_lock::ReleaseCursor,
+    locale::LocaleKey,
+    states::{HideInSettings, SettingsMenu},
+    widgets::{ButtonSize, Theme},
+    GameState, LevelState,
+};
+
+// only means anything while Playing, gameplay systems also run_in_state(PauseState::Running)
+// added with the other states in lib.rs, channels hook into it before this plugin is built
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub enum PauseState {
+    Running,
+    Paused,
+}
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_enter_system(PauseState::Paused, setup_pause_menu)
+            .add_enter_system(PauseState::Paused, pause_world)
+            .add_system(
+                unpause_hotkey
+                    .run_in_state(GameState::Playing)
+                    .run_in_state(PauseState::Paused)
+                    .run_in_state(SettingsMenu::Closed),
+            )
+            .add_system(
+                click_button
+                    .run_in_state(GameState::Playing)
+                    .run_in_state(PauseState::Paused)
+                    .run_in_state(SettingsMenu::Closed),
+            )
+            .add_exit_system(PauseState::Paused, resume_world)
+            .add_exit_system(PauseState::Paused, despawn_pause_menu)
+            // leaving the level for any reason, don't come back to a paused one
+            .add_exit_system(GameState::Playing, unpause);
+    }
+}
+
+#[derive(Component)]
+struct PauseMenu;
+
+// physics state before pausing, the debug toggle may have turned it off
+struct PausedPhysics(PhysicsState);
+
+// tweens we stopped, so resume leaves ones that were already paused alone
+#[derive(Component)]
+struct PausedAnimator;
+
+#[derive(Component, Debug, Copy, Clone)]
+enum PauseButton {
+    Resume,
+    Restart,
+    Settings,
+    Quit,
+}
+
+impl LocaleKey for PauseButton {
+    fn key(&self) -> &'static str {
+        match self {
+            PauseButton::Resume => "pause.resume",
+            PauseButton::Restart => "pause.restart",
+            PauseButton::Settings => "pause.settings",
+            PauseButton::Quit => "pause.quit",
+        }
+    }
+}
+
+impl PauseButton {
+    fn iter() -> impl Iterator<Item = Self> {
+        [
+            PauseButton::Resume,
+            PauseButton::Restart,
+            PauseButton::Settings,
+            PauseButton::Quit,
+        ]
+        .into_iter()
+    }
+}
+
+fn setup_pause_menu(mut commands: Commands, theme: Theme) {
+    commands
+        .spawn_bundle(theme.overlay(0.6))
+        .insert(PauseMenu)
+        .insert(HideInSettings)
+        .insert(ReleaseCursor)
+        .insert(Name::new("ui Pause Menu"))
+        .with_children(|parent| {
+            parent.spawn_bundle(theme.title("pause.title"));
+            for b in PauseButton::iter() {
+                theme.button(parent, b, ButtonSize::Large);
+            }
+        });
+}
+
+fn despawn_pause_menu(mut commands: Commands, query: Query<Entity, With<PauseMenu>>) {
+    for e in query.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+}
+
+// audio channels are paused by the mixer, see MixerChannel::IN_GAME
+fn pause_world(
+    mut commands: Commands,
+    physics_state: Res<CurrentState<PhysicsState>>,
+    mut animator_query: Query<(Entity, &mut Animator<Transform>)>,
+) {
+    commands.insert_resource(PausedPhysics(physics_state.0.clone()));
+    commands.insert_resource(NextState(PhysicsState::Paused));
+
+    for (e, mut animator) in animator_query.iter_mut() {
+        if animator.state == AnimatorState::Playing {
+            animator.state = AnimatorState::Paused;
+            commands.entity(e).insert(PausedAnimator);
+        }
+    }
+}
+
+fn resume_world(
+    mut commands: Commands,
+    paused_physics: Option<Res<PausedPhysics>>,
+    mut animator_query: Query<(Entity, &mut Animator<Transform>), With<PausedAnimator>>,
+) {
+    if let Some(paused_physics) = paused_physics {
+        commands.insert_resource(NextState(paused_physics.0.clone()));
+        commands.remove_resource::<PausedPhysics>();
+    }
+
+    for (e, mut animator) in animator_query.iter_mut() {
+        animator.state = AnimatorState::Playing;
+        commands.entity(e).remove::<PausedAnimator>();
+    }
+}
+
+fn unpause(mut commands: Commands) {
+    commands.insert_resource(NextState(PauseState::Running));
+}
+
+fn unpause_hotkey(mut commands: Commands, actions: Res<ActionState>) {
+    if actions.just_pressed(Action::Pause) {
+        commands.insert_resource(NextState(PauseState::Running));
+    }
+}
+
+fn click_button(
+    mut commands: Commands,
+    interaction_query: Query<(&Interaction, &PauseButton), (Changed<Interaction>, With<Button>)>,
+    level_state: Res<CurrentState<LevelState>>,
+) {
+    for (interaction, btn) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            match btn {
+                PauseButton::Resume => {
+                    commands.insert_resource(NextState(PauseState::Running));
+                }
+                PauseButton::Restart => {
+                    // entering the same states again tears the level down and builds it fresh
+                    commands.insert_resource(NextState(GameState::Playing));
+                    commands.insert_resource(NextState(level_state.0.clone()));
+                }
+                PauseButton::Settings => {
+                    commands.insert_resource(NextState(SettingsMenu::Open));
+                }
+                PauseButton::Quit => {
+                    commands.insert_resource(NextState(GameState::Menu));
+                }
+            }
+        }
+    }
+}
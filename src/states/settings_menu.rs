@@ -0,0 +1,538 @@
+// settings screen, opens over the main menu or the pause menu and saves every change straight away
+use bevy::prelude::*;
+use iyes_loopless::prelude::*;
+
+use crate::{
+    actions::{Action, ActionState, Device, Rebinding},
+    cursor_lock::ReleaseCursor,
+    graphics::{DisplayMode, MSAA_SAMPLES, RESOLUTIONS},
+    locale::{Language, Strings},
+    mixer::Bus,
+    settings::Settings,
+    widgets::{ButtonSize, Theme},
+};
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub enum SettingsMenu {
+    Closed,
+    Open,
+}
+
+pub struct SettingsMenuPlugin;
+
+impl Plugin for SettingsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_loopless_state(SettingsMenu::Closed)
+            .init_resource::<SettingsPage>()
+            .add_enter_system(SettingsMenu::Open, setup_settings_menu)
+            .add_enter_system(SettingsMenu::Open, hide_menus)
+            .add_system(close_hotkey.run_in_state(SettingsMenu::Open))
+            .add_system(click_button.run_in_state(SettingsMenu::Open))
+            .add_system(
+                build_page
+                    .run_in_state(SettingsMenu::Open)
+                    .after(click_button),
+            )
+            .add_system(
+                update_values
+                    .run_in_state(SettingsMenu::Open)
+                    .after(build_page),
+            )
+            .add_exit_system(SettingsMenu::Open, despawn_settings_menu)
+            .add_exit_system(SettingsMenu::Open, show_menus)
+            .add_exit_system(SettingsMenu::Open, cancel_rebind);
+    }
+}
+
+// Menus under the settings screen, hidden so the d-pad can't reach them
+#[derive(Component)]
+pub struct HideInSettings;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+enum SettingsPage {
+    #[default]
+    Graphics,
+    Audio,
+    Controls,
+}
+
+impl SettingsPage {
+    fn iter() -> impl Iterator<Item = Self> {
+        [
+            SettingsPage::Graphics,
+            SettingsPage::Audio,
+            SettingsPage::Controls,
+        ]
+        .into_iter()
+    }
+
+    fn key(&self) -> &'static str {
+        match self {
+            SettingsPage::Graphics => "settings.graphics",
+            SettingsPage::Audio => "settings.audio",
+            SettingsPage::Controls => "settings.controls",
+        }
+    }
+
+    fn options(&self) -> Vec<Setting> {
+        match self {
+            SettingsPage::Graphics => vec![
+                Setting::Language,
+                Setting::DisplayMode,
+                Setting::Resolution,
+                Setting::Msaa,
+                Setting::Vsync,
+                Setting::Fov,
+            ],
+            SettingsPage::Audio => vec![
+                Setting::Master,
+                Setting::Bus(Bus::Music),
+                Setting::Bus(Bus::Voice),
+                Setting::Bus(Bus::Sfx),
+                Setting::Subtitles,
+                Setting::SoundCaptions,
+            ],
+            SettingsPage::Controls => {
+                let mut options = vec![Setting::Sensitivity, Setting::InvertY];
+                options.extend(REBINDABLE.iter().map(|a| Setting::Binding(*a)));
+                options
+            }
+        }
+    }
+}
+
+// the rest are menu and debug keys
+const REBINDABLE: [Action; 12] = [
+    Action::MoveForward,
+    Action::MoveBack,
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::Jump,
+    Action::Run,
+    Action::FireBlue,
+    Action::FireYellow,
+    Action::Grab,
+    Action::Interact,
+    Action::UnlockCursor,
+    Action::SkipDialog,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Setting {
+    Language,
+    DisplayMode,
+    Resolution,
+    Msaa,
+    Vsync,
+    Fov,
+    Master,
+    Bus(Bus),
+    Subtitles,
+    SoundCaptions,
+    Sensitivity,
+    InvertY,
+    Binding(Action),
+}
+
+impl Setting {
+    // string table key
+    fn name(&self) -> String {
+        match self {
+            Setting::Language => "settings.language".to_string(),
+            Setting::DisplayMode => "settings.display".to_string(),
+            Setting::Resolution => "settings.resolution".to_string(),
+            Setting::Msaa => "settings.msaa".to_string(),
+            Setting::Vsync => "settings.vsync".to_string(),
+            Setting::Fov => "settings.fov".to_string(),
+            Setting::Master => "settings.master".to_string(),
+            Setting::Bus(Bus::Music) => "settings.music".to_string(),
+            Setting::Bus(Bus::Voice) => "settings.voice".to_string(),
+            Setting::Bus(Bus::Sfx) => "settings.sfx".to_string(),
+            Setting::Subtitles => "settings.subtitles".to_string(),
+            Setting::SoundCaptions => "settings.sound_captions".to_string(),
+            Setting::Sensitivity => "settings.sensitivity".to_string(),
+            Setting::InvertY => "settings.invert_y".to_string(),
+            Setting::Binding(action) => format!("action.{:?}", action),
+        }
+    }
+
+    fn value(&self, settings: &Settings, rebinding: &Rebinding, strings: &Strings) -> String {
+        let on_off = |b: bool| strings.get(if b { "settings.on" } else { "settings.off" });
+        let percent = |v: f32| format!("{:.0}%", v * 100.0);
+        match *self {
+            Setting::Language => settings.language.name().to_string(),
+            Setting::DisplayMode => strings.get(match settings.graphics.mode {
+                DisplayMode::Windowed => "settings.windowed",
+                DisplayMode::Borderless => "settings.borderless",
+                DisplayMode::Fullscreen => "settings.fullscreen",
+            }),
+            Setting::Resolution => {
+                let (width, height) = settings.graphics.resolution;
+                format!("{}x{}", width, height)
+            }
+            Setting::Msaa => match settings.graphics.msaa() {
+                Msaa { samples: 1 } => on_off(false),
+                Msaa { samples } => format!("{}x", samples),
+            },
+            Setting::Vsync => on_off(settings.graphics.vsync),
+            Setting::Fov => format!("{:.0}", settings.graphics.fov),
+            Setting::Master => percent(settings.audio.master),
+            Setting::Bus(bus) => percent(settings.audio.level(bus)),
+            Setting::Subtitles => on_off(settings.subtitles.enabled),
+            Setting::SoundCaptions => on_off(settings.subtitles.sounds),
+            Setting::Sensitivity => format!("{:.2}", settings.input.mouse.sensitivity),
+            Setting::InvertY => on_off(settings.input.mouse.invert_y),
+            Setting::Binding(action) if rebinding.0 == Some(action) => {
+                strings.get("settings.press_key")
+            }
+            Setting::Binding(action) => {
+                let labels = settings
+                    .input
+                    .get(action)
+                    .iter()
+                    .filter(|b| b.device() != Device::Gamepad)
+                    .map(|b| b.label())
+                    .collect::<Vec<_>>();
+                if labels.is_empty() {
+                    strings.get("settings.unbound")
+                } else {
+                    labels.join(" / ")
+                }
+            }
+        }
+    }
+
+    // step is -1 or 1, toggles ignore the direction
+    fn change(&self, settings: &mut Settings, step: i32) {
+        match *self {
+            Setting::Language => {
+                settings.language = cycle(&Language::ALL, settings.language, step);
+            }
+            Setting::DisplayMode => {
+                let modes = [
+                    DisplayMode::Windowed,
+                    DisplayMode::Borderless,
+                    DisplayMode::Fullscreen,
+                ];
+                settings.graphics.mode = cycle(&modes, settings.graphics.mode, step);
+            }
+            Setting::Resolution => {
+                settings.graphics.resolution =
+                    cycle(&RESOLUTIONS, settings.graphics.resolution, step);
+            }
+            Setting::Msaa => {
+                settings.graphics.msaa = cycle(&MSAA_SAMPLES, settings.graphics.msaa, step);
+            }
+            Setting::Vsync => settings.graphics.vsync = !settings.graphics.vsync,
+            Setting::Fov => {
+                settings.graphics.fov =
+                    (settings.graphics.fov + 5.0 * step as f32).clamp(30.0, 110.0);
+            }
+            Setting::Master => {
+                settings.audio.master = volume_step(settings.audio.master, step);
+            }
+            Setting::Bus(bus) => {
+                let level = match bus {
+                    Bus::Music => &mut settings.audio.music,
+                    Bus::Voice => &mut settings.audio.voice,
+                    Bus::Sfx => &mut settings.audio.sfx,
+                };
+                *level = volume_step(*level, step);
+            }
+            Setting::Subtitles => settings.subtitles.enabled = !settings.subtitles.enabled,
+            Setting::SoundCaptions => settings.subtitles.sounds = !settings.subtitles.sounds,
+            Setting::Sensitivity => {
+                let sensitivity = settings.input.mouse.sensitivity + 0.01 * step as f32;
+                settings.input.mouse.sensitivity = sensitivity.clamp(0.01, 0.5);
+            }
+            Setting::InvertY => settings.input.mouse.invert_y = !settings.input.mouse.invert_y,
+            // handled by the rebind system
+            Setting::Binding(_) => {}
+        }
+    }
+
+    // toggles and rebinds are a single button, numbers and lists get arrows
+    fn has_arrows(&self) -> bool {
+        !matches!(
+            self,
+            Setting::Vsync
+                | Setting::Subtitles
+                | Setting::SoundCaptions
+                | Setting::InvertY
+                | Setting::Binding(_)
+        )
+    }
+}
+
+// next or previous entry, wrapping, anything not in the list starts from the first
+fn cycle<T: Copy + PartialEq>(options: &[T], current: T, step: i32) -> T {
+    let len = options.len() as i32;
+    let index = match options.iter().position(|o| *o == current) {
+        Some(i) => (i as i32 + step).rem_euclid(len),
+        None => 0,
+    };
+    options[index as usize]
+}
+
+fn volume_step(level: f32, step: i32) -> f32 {
+    // round so repeated steps don't drift away from tidy numbers
+    ((level + 0.1 * step as f32) * 10.0)
+        .round()
+        .clamp(0.0, 10.0)
+        / 10.0
+}
+
+#[derive(Component)]
+struct SettingsRoot;
+
+// page content, rebuilt when the page changes
+#[derive(Component)]
+struct SettingsContent;
+
+#[derive(Component, Clone, Copy)]
+enum SettingsButton {
+    Page(SettingsPage),
+    Change(Setting, i32),
+    Rebind(Action),
+    Back,
+}
+
+#[derive(Component)]
+struct SettingValue(Setting);
+
+fn setup_settings_menu(mut commands: Commands, theme: Theme, mut page: ResMut<SettingsPage>) {
+    *page = SettingsPage::default();
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::FlexStart,
+                padding: UiRect::all(Val::Px(20.0)),
+                ..default()
+            },
+            color: Color::rgba(0.0, 0.0, 0.0, 0.85).into(),
+            ..default()
+        })
+        .insert(SettingsRoot)
+        .insert(ReleaseCursor)
+        .insert(Name::new("ui Settings"))
+        .with_children(|parent| {
+            parent.spawn_bundle(theme.heading("settings.title", Color::GOLD, default()));
+
+            // page tabs
+            parent
+                .spawn_bundle(theme.panel(Style {
+                    margin: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                }))
+                .with_children(|parent| {
+                    for p in SettingsPage::iter() {
+                        spawn_button(parent, &theme, p.key(), SettingsButton::Page(p), None);
+                    }
+                });
+
+            parent
+                .spawn_bundle(theme.panel(Style {
+                    flex_direction: FlexDirection::ColumnReverse,
+                    flex_grow: 1.0,
+                    ..default()
+                }))
+                .insert(SettingsContent);
+
+            spawn_button(parent, &theme, "settings.back", SettingsButton::Back, None);
+        });
+}
+
+// shows the setting's value when there is one, otherwise the key's text
+fn spawn_button(
+    parent: &mut ChildBuilder,
+    theme: &Theme,
+    key: &str,
+    button: SettingsButton,
+    value: Option<Setting>,
+) {
+    parent
+        .spawn_bundle(theme.button_bundle(ButtonSize::Small))
+        .with_children(|parent| match value {
+            Some(setting) => {
+                parent
+                    .spawn_bundle(theme.button_text("", ButtonSize::Small))
+                    .insert(SettingValue(setting));
+            }
+            None => {
+                let text = theme.button_text(theme.strings.get(key), ButtonSize::Small);
+                parent.spawn_bundle(theme.translated(key, text));
+            }
+        })
+        .insert(button);
+}
+
+fn build_page(
+    mut commands: Commands,
+    page: Res<SettingsPage>,
+    content_query: Query<Entity, Added<SettingsContent>>,
+    existing_query: Query<Entity, With<SettingsContent>>,
+    theme: Theme,
+) {
+    // new menu, or a different tab was picked
+    let content = match content_query.get_single() {
+        Ok(content) => content,
+        Err(_) if page.is_changed() => match existing_query.get_single() {
+            Ok(content) => content,
+            Err(_) => return,
+        },
+        Err(_) => return,
+    };
+
+    commands.entity(content).despawn_descendants();
+    commands.entity(content).with_children(|parent| {
+        for setting in page.options() {
+            parent
+                .spawn_bundle(theme.panel(Style {
+                    size: Size::new(Val::Px(460.0), Val::Auto),
+                    justify_content: JustifyContent::SpaceBetween,
+                    align_items: AlignItems::Center,
+                    ..default()
+                }))
+                .with_children(|parent| {
+                    parent.spawn_bundle(theme.label(&setting.name(), default()));
+
+                    parent
+                        .spawn_bundle(theme.panel(Style {
+                            align_items: AlignItems::Center,
+                            ..default()
+                        }))
+                        .with_children(|parent| {
+                            if let Setting::Binding(action) = setting {
+                                spawn_button(
+                                    parent,
+                                    &theme,
+                                    "",
+                                    SettingsButton::Rebind(action),
+                                    Some(setting),
+                                );
+                            } else if setting.has_arrows() {
+                                spawn_button(
+                                    parent,
+                                    &theme,
+                                    "settings.previous",
+                                    SettingsButton::Change(setting, -1),
+                                    None,
+                                );
+                                parent
+                                    .spawn_bundle(TextBundle {
+                                        style: Style {
+                                            min_size: Size::new(Val::Px(110.0), Val::Auto),
+                                            justify_content: JustifyContent::Center,
+                                            ..default()
+                                        },
+                                        text: Text {
+                                            sections: vec![theme
+                                                .fonts
+                                                .sub_title(String::new(), Color::GOLD)],
+                                            alignment: TextAlignment::CENTER,
+                                        },
+                                        ..default()
+                                    })
+                                    .insert(SettingValue(setting));
+                                spawn_button(
+                                    parent,
+                                    &theme,
+                                    "settings.next",
+                                    SettingsButton::Change(setting, 1),
+                                    None,
+                                );
+                            } else {
+                                spawn_button(
+                                    parent,
+                                    &theme,
+                                    "",
+                                    SettingsButton::Change(setting, 1),
+                                    Some(setting),
+                                );
+                            }
+                        });
+                });
+        }
+    });
+}
+
+fn update_values(
+    settings: Res<Settings>,
+    rebinding: Res<Rebinding>,
+    strings: Res<Strings>,
+    mut value_query: Query<(&SettingValue, &mut Text, ChangeTrackers<SettingValue>)>,
+) {
+    let changed = settings.is_changed() || rebinding.is_changed() || strings.is_changed();
+    for (SettingValue(setting), mut text, tracker) in value_query.iter_mut() {
+        if changed || tracker.is_added() {
+            text.sections[0].value = setting.value(&settings, &rebinding, &strings);
+        }
+    }
+}
+
+fn click_button(
+    mut commands: Commands,
+    interaction_query: Query<(&Interaction, &SettingsButton), (Changed<Interaction>, With<Button>)>,
+    mut page: ResMut<SettingsPage>,
+    mut rebinding: ResMut<Rebinding>,
+    mut settings: ResMut<Settings>,
+    mut mouse: ResMut<Input<MouseButton>>,
+) {
+    for (interaction, btn) in interaction_query.iter() {
+        if *interaction != Interaction::Clicked || rebinding.0.is_some() {
+            continue;
+        }
+        match *btn {
+            SettingsButton::Page(p) => {
+                if *page != p {
+                    *page = p;
+                }
+            }
+            SettingsButton::Change(setting, step) => {
+                setting.change(&mut settings, step);
+                settings.save();
+            }
+            // saved by the rebind system once a key is pressed
+            SettingsButton::Rebind(action) => {
+                rebinding.0 = Some(action);
+                // the click itself shouldn't become the binding
+                mouse.clear();
+            }
+            SettingsButton::Back => commands.insert_resource(NextState(SettingsMenu::Closed)),
+        }
+    }
+}
+
+fn close_hotkey(mut commands: Commands, actions: Res<ActionState>, rebinding: Res<Rebinding>) {
+    // escape while rebinding only cancels the rebind
+    if actions.just_pressed(Action::Pause) && rebinding.0.is_none() && !rebinding.is_changed() {
+        commands.insert_resource(NextState(SettingsMenu::Closed));
+    }
+}
+
+fn cancel_rebind(mut rebinding: ResMut<Rebinding>) {
+    rebinding.0 = None;
+}
+
+fn despawn_settings_menu(mut commands: Commands, query: Query<Entity, With<SettingsRoot>>) {
+    for e in query.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+}
+
+fn hide_menus(mut query: Query<&mut Visibility, With<HideInSettings>>) {
+    for mut visibility in query.iter_mut() {
+        visibility.is_visible = false;
+    }
+}
+
+fn show_menus(mut query: Query<&mut Visibility, With<HideInSettings>>) {
+    for mut visibility in query.iter_mut() {
+        visibility.is_visible = true;
+    }
+}
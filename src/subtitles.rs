@@ -0,0 +1,416 @@
+// captions for voice lines and sound effects, timed against the audio channel playing them
+use std::{any::type_name, collections::HashMap};
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use bevy_kira_audio::prelude::*;
+use bevy_kira_audio::{AudioSource, InstanceHandle, PlaybackState};
+use iyes_loopless::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assets::{CaptionAssets, FontAssets},
+    settings::Settings,
+    states::PauseState,
+    GameState,
+};
+
+pub struct SubtitlePlugin;
+
+impl Plugin for SubtitlePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<CaptionSet>()
+            .init_asset_loader::<CaptionLoader>()
+            .init_resource::<Captions>()
+            .add_event::<PlayCaption>()
+            .add_enter_system(GameState::Playing, spawn_caption_box)
+            .add_exit_system(GameState::Playing, clear_captions)
+            .add_system(start_captions.run_in_state(GameState::Playing))
+            .add_system(
+                update_caption_box
+                    .run_in_state(GameState::Playing)
+                    .after(start_captions),
+            );
+    }
+}
+
+pub trait CaptionApp {
+    // keep captions for sounds on this channel in sync with playback
+    fn add_caption_channel<T: Resource>(&mut self) -> &mut Self;
+}
+
+impl CaptionApp for App {
+    fn add_caption_channel<T: Resource>(&mut self) -> &mut Self {
+        self.add_system(
+            track_captions::<T>
+                .run_in_state(GameState::Playing)
+                .run_in_state(PauseState::Running)
+                .after(start_captions)
+                .before(update_caption_box),
+        )
+    }
+}
+
+const CAPTION_SIZE: f32 = 22.0;
+// sound effects are over before anyone can read them
+const SOUND_HOLD: f32 = 1.5;
+const SPEAKER_COLOR: Color = Color::rgb(0.5, 0.5, 1.0);
+const SOUND_COLOR: Color = Color::rgb(0.75, 0.75, 0.75);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SubtitleSettings {
+    pub enabled: bool,
+    // captions for sound effects, not just speech
+    pub sounds: bool,
+}
+
+impl Default for SubtitleSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            sounds: true,
+        }
+    }
+}
+
+// Every captioned sound, keyed by audio asset path
+#[derive(Deserialize, TypeUuid)]
+#[uuid = "8f3e1b2a-6c4d-4e5f-a7b8-9c0d1e2f3a4b"]
+pub struct CaptionSet {
+    pub captions: HashMap<String, Caption>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Caption {
+    #[serde(default)]
+    pub speaker: Option<String>,
+    // a sound effect rather than speech, shown in brackets
+    #[serde(default)]
+    pub sound: bool,
+    // (seconds into the sound, text shown from then on)
+    pub lines: Vec<(f32, String)>,
+}
+
+#[derive(Default)]
+pub struct CaptionLoader;
+
+impl AssetLoader for CaptionLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let set = ron::de::from_bytes::<CaptionSet>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(set));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["captions.ron"]
+    }
+}
+
+// Send after playing a sound to caption it
+pub struct PlayCaption {
+    channel: &'static str,
+    source: Option<Handle<AudioSource>>,
+    instance: Option<InstanceHandle>,
+    // used when the sound has no captions
+    fallback: Option<Caption>,
+    duration: f32,
+}
+
+impl PlayCaption {
+    pub fn new<T: Resource>(source: Handle<AudioSource>, instance: InstanceHandle) -> Self {
+        Self {
+            channel: type_name::<T>(),
+            source: Some(source),
+            instance: Some(instance),
+            fallback: None,
+            duration: 0.0,
+        }
+    }
+
+    // text with no sound behind it, shown for duration seconds
+    pub fn text<T: Resource>(speaker: Option<String>, text: String, duration: f32) -> Self {
+        Self {
+            channel: type_name::<T>(),
+            source: None,
+            instance: None,
+            fallback: Some(Caption {
+                speaker,
+                sound: false,
+                lines: vec![(0.0, text)],
+            }),
+            duration,
+        }
+    }
+
+    pub fn with_fallback(mut self, speaker: Option<String>, text: String) -> Self {
+        if !text.is_empty() {
+            self.fallback = Some(Caption {
+                speaker,
+                sound: false,
+                lines: vec![(0.0, text)],
+            });
+        }
+        self
+    }
+}
+
+struct ActiveCaption {
+    channel: &'static str,
+    // None for text only captions
+    instance: Option<InstanceHandle>,
+    caption: Caption,
+    // seconds into the sound
+    position: f32,
+    // time since the caption started, counts while paused too
+    age: f32,
+    duration: f32,
+    paused: bool,
+    stopped: bool,
+}
+
+impl ActiveCaption {
+    fn text(&self) -> Option<&str> {
+        self.caption
+            .lines
+            .iter()
+            .rev()
+            .find(|(start, _)| *start <= self.position)
+            .map(|(_, text)| text.as_str())
+    }
+
+    fn finished(&self) -> bool {
+        let hold = if self.caption.sound { SOUND_HOLD } else { 0.0 };
+        match self.instance {
+            Some(_) => self.stopped && self.age >= hold,
+            None => self.age >= self.duration,
+        }
+    }
+}
+
+// Captions on screen right now, oldest first
+#[derive(Default)]
+pub struct Captions {
+    active: Vec<ActiveCaption>,
+}
+
+impl Captions {
+    // current line being said on a channel, for speech bubbles and such
+    pub fn current<T: Resource>(&self) -> Option<&str> {
+        self.active
+            .iter()
+            .rev()
+            .filter(|c| c.channel == type_name::<T>() && !c.caption.sound && !c.stopped)
+            .find_map(|c| c.text())
+    }
+}
+
+#[derive(Component)]
+struct CaptionBox;
+
+#[derive(Component)]
+struct CaptionText;
+
+fn spawn_caption_box(mut commands: Commands) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Percent(20.0),
+                    right: Val::Percent(20.0),
+                    bottom: Val::Percent(8.0),
+                    ..default()
+                },
+                justify_content: JustifyContent::Center,
+                display: Display::None,
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+            color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+            ..default()
+        })
+        .insert(CaptionBox)
+        .insert(Name::new("ui Captions"))
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle {
+                    text: Text {
+                        sections: vec![],
+                        alignment: TextAlignment {
+                            vertical: VerticalAlign::Center,
+                            horizontal: HorizontalAlign::Center,
+                        },
+                    },
+                    ..default()
+                })
+                .insert(CaptionText);
+        });
+}
+
+fn clear_captions(mut captions: ResMut<Captions>) {
+    captions.active.clear();
+}
+
+fn start_captions(
+    mut play_events: EventReader<PlayCaption>,
+    mut captions: ResMut<Captions>,
+    caption_assets: Res<CaptionAssets>,
+    caption_sets: Res<Assets<CaptionSet>>,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+) {
+    // lines not translated yet fall back to english
+    let sets = [
+        caption_sets.get(caption_assets.captions(settings.language)),
+        caption_sets.get(&caption_assets.en),
+    ];
+
+    for event in play_events.iter() {
+        let path = event
+            .source
+            .as_ref()
+            .and_then(|source| asset_server.get_handle_path(source))
+            .map(|path| path.path().to_string_lossy().replace('\\', "/"));
+
+        let caption = path
+            .as_ref()
+            .and_then(|path| sets.iter().flatten().find_map(|set| set.captions.get(path)))
+            .or(event.fallback.as_ref());
+
+        let caption = match caption {
+            Some(caption) => caption.clone(),
+            None => {
+                if let Some(path) = path {
+                    warn!("no captions for {}", path);
+                }
+                continue;
+            }
+        };
+
+        captions.active.push(ActiveCaption {
+            channel: event.channel,
+            instance: event.instance.clone(),
+            caption,
+            position: 0.0,
+            age: 0.0,
+            duration: event.duration,
+            paused: false,
+            stopped: false,
+        });
+    }
+}
+
+// follow playback so pausing holds the caption and stopping or skipping clears it
+fn track_captions<T: Resource>(
+    mut captions: ResMut<Captions>,
+    channel: Res<AudioChannel<T>>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_seconds();
+    for caption in captions
+        .active
+        .iter_mut()
+        .filter(|c| c.channel == type_name::<T>())
+    {
+        caption.age += delta;
+
+        let instance = match &caption.instance {
+            Some(instance) => instance.clone(),
+            None => continue,
+        };
+        match channel.state(instance) {
+            PlaybackState::Playing { position } | PlaybackState::Stopping { position } => {
+                caption.position = position as f32;
+                caption.paused = false;
+            }
+            PlaybackState::Paused { position } | PlaybackState::Pausing { position } => {
+                caption.position = position as f32;
+                caption.paused = true;
+            }
+            PlaybackState::Queued => {}
+            PlaybackState::Stopped => caption.stopped = true,
+        }
+    }
+}
+
+fn update_caption_box(
+    mut captions: ResMut<Captions>,
+    settings: Res<Settings>,
+    font_assets: Res<FontAssets>,
+    mut box_query: Query<&mut Style, With<CaptionBox>>,
+    mut text_query: Query<&mut Text, With<CaptionText>>,
+) {
+    captions.active.retain(|c| !c.finished());
+
+    let mut sections = Vec::new();
+    if settings.subtitles.enabled {
+        for caption in captions.active.iter() {
+            if caption.caption.sound && !settings.subtitles.sounds {
+                continue;
+            }
+            // a stopped voice line is gone, only sound effects linger
+            if caption.stopped && !caption.caption.sound {
+                continue;
+            }
+            let text = match caption.text() {
+                Some(text) => text,
+                None => continue,
+            };
+
+            let first = sections.is_empty();
+            let alpha = if caption.paused { 0.5 } else { 1.0 };
+            let mut push = |value: String, mut color: Color| {
+                color.set_a(alpha);
+                let mut section = font_assets.sub_title(value, color);
+                section.style.font_size = CAPTION_SIZE;
+                sections.push(section);
+            };
+
+            if !first {
+                push("\n".to_string(), Color::WHITE);
+            }
+            if caption.caption.sound {
+                push(format!("[{}]", text), SOUND_COLOR);
+            } else {
+                if let Some(speaker) = &caption.caption.speaker {
+                    push(format!("{}: ", speaker), SPEAKER_COLOR);
+                }
+                push(text.to_string(), Color::WHITE);
+            }
+        }
+    }
+
+    for mut style in box_query.iter_mut() {
+        let display = if sections.is_empty() {
+            Display::None
+        } else {
+            Display::Flex
+        };
+        if style.display != display {
+            style.display = display;
+        }
+    }
+
+    for mut text in text_query.iter_mut() {
+        let changed = text.sections.len() != sections.len()
+            || text
+                .sections
+                .iter()
+                .zip(sections.iter())
+                .any(|(a, b)| a.value != b.value || a.style.color != b.style.color);
+        if changed {
+            text.sections = sections.clone();
+        }
+    }
+}
@@ -0,0 +1,283 @@
+// small ui toolkit, so hud and menus are declared instead of built node by node
+// everything is themed from FontAssets and ButtonColors, hover colours come from update_buttons
+// text taking a key comes from the string table and follows language changes
+use std::marker::PhantomData;
+
+use bevy::{
+    ecs::system::{EntityCommands, SystemParam},
+    prelude::*,
+};
+
+use crate::{
+    assets::{ButtonColors, FontAssets, CLEAR},
+    locale::{LocaleKey, Strings, Translate},
+};
+
+pub const TEXT_COLOR: Color = Color::rgb(0.9, 0.9, 0.9);
+
+pub trait WidgetApp {
+    // keeps Readout<R> text in step with the resource
+    fn add_readout<R: Resource>(&mut self) -> &mut Self;
+}
+
+impl WidgetApp for App {
+    fn add_readout<R: Resource>(&mut self) -> &mut Self {
+        self.add_system_to_stage(CoreStage::PostUpdate, update_readouts::<R>)
+    }
+}
+
+// where an absolutely placed widget sits, offsets are in pixels in from that corner
+#[derive(Clone, Copy, Debug)]
+pub enum Anchor {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Anchor {
+    pub fn style(self, x: f32, y: f32) -> Style {
+        let (x, y) = (Val::Px(x), Val::Px(y));
+        let position = match self {
+            Anchor::TopLeft => UiRect {
+                left: x,
+                top: y,
+                ..default()
+            },
+            Anchor::TopRight => UiRect {
+                right: x,
+                top: y,
+                ..default()
+            },
+            Anchor::BottomLeft => UiRect {
+                left: x,
+                bottom: y,
+                ..default()
+            },
+            Anchor::BottomRight => UiRect {
+                right: x,
+                bottom: y,
+                ..default()
+            },
+        };
+        Style {
+            position_type: PositionType::Absolute,
+            position,
+            ..default()
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ButtonSize {
+    // menu buttons, h1 text at a fixed width
+    Large,
+    // fits the text, for rows of small controls
+    Small,
+}
+
+// label then a value filled in from R, add the resource with add_readout
+#[derive(Component)]
+pub struct Readout<R: Resource> {
+    value: fn(&R) -> String,
+    color: Option<fn(&R) -> Color>,
+}
+
+impl<R: Resource> Readout<R> {
+    pub fn new(value: fn(&R) -> String) -> Self {
+        Self { value, color: None }
+    }
+
+    pub fn with_color(mut self, color: fn(&R) -> Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+fn update_readouts<R: Resource>(
+    resource: Option<Res<R>>,
+    mut readout_query: Query<(&Readout<R>, &mut Text, ChangeTrackers<Readout<R>>)>,
+) {
+    let resource = match resource {
+        Some(resource) => resource,
+        None => return,
+    };
+    for (readout, mut text, tracker) in readout_query.iter_mut() {
+        if !resource.is_changed() && !tracker.is_added() {
+            continue;
+        }
+        if let Some(section) = text.sections.last_mut() {
+            section.value = (readout.value)(&resource);
+            if let Some(color) = readout.color {
+                section.style.color = color(&resource);
+            }
+        }
+    }
+}
+
+#[derive(Bundle)]
+pub struct TranslatedText {
+    #[bundle]
+    pub text: TextBundle,
+    pub translate: Translate,
+}
+
+#[derive(SystemParam)]
+pub struct Theme<'w, 's> {
+    pub fonts: Res<'w, FontAssets>,
+    pub colors: Res<'w, ButtonColors>,
+    pub strings: Res<'w, Strings>,
+    #[system_param(ignore)]
+    marker: PhantomData<&'s ()>,
+}
+
+impl<'w, 's> Theme<'w, 's> {
+    // for text made up as the game runs, use label for anything in the string table
+    pub fn text(&self, value: impl Into<String>, style: Style) -> TextBundle {
+        TextBundle {
+            style,
+            text: Text {
+                sections: vec![self.fonts.sub_title(value.into(), Color::WHITE)],
+                alignment: Default::default(),
+            },
+            ..default()
+        }
+    }
+
+    pub fn label(&self, key: &str, style: Style) -> TranslatedText {
+        self.translated(key, self.text(self.strings.get(key), style))
+    }
+
+    pub fn heading(&self, key: &str, color: Color, style: Style) -> TranslatedText {
+        let text = TextBundle {
+            style,
+            text: Text {
+                sections: vec![self.fonts.h1(self.strings.get(key), color)],
+                alignment: Default::default(),
+            },
+            ..default()
+        };
+        self.translated(key, text)
+    }
+
+    // pair with a Readout, the value goes in the second section
+    pub fn readout(&self, key: &str, style: Style) -> TranslatedText {
+        let text = TextBundle {
+            style,
+            text: Text {
+                sections: vec![
+                    self.fonts.h1(self.strings.get(key), Color::WHITE),
+                    self.fonts.h1(String::new(), Color::GOLD),
+                ],
+                alignment: Default::default(),
+            },
+            ..default()
+        };
+        self.translated(key, text)
+    }
+
+    // invisible container, lay children out with the style
+    pub fn panel(&self, style: Style) -> NodeBundle {
+        NodeBundle {
+            style,
+            color: CLEAR.into(),
+            ..default()
+        }
+    }
+
+    // full screen, dims whatever is underneath, children stack down the middle
+    pub fn overlay(&self, alpha: f32) -> NodeBundle {
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            color: Color::rgba(0.0, 0.0, 0.0, alpha).into(),
+            ..default()
+        }
+    }
+
+    pub fn title(&self, key: &str) -> TranslatedText {
+        let text = TextBundle {
+            style: Style {
+                margin: UiRect::all(Val::Px(20.0)),
+                ..default()
+            },
+            text: Text {
+                sections: vec![self.fonts.title(self.strings.get(key), Color::GOLD)],
+                alignment: Default::default(),
+            },
+            ..default()
+        };
+        self.translated(key, text)
+    }
+
+    // any text bundle, its first section is filled from the key
+    pub fn translated(&self, key: &str, text: TextBundle) -> TranslatedText {
+        TranslatedText {
+            text,
+            translate: Translate::new(key),
+        }
+    }
+
+    pub fn button_bundle(&self, size: ButtonSize) -> ButtonBundle {
+        let style = match size {
+            ButtonSize::Large => Style {
+                size: Size::new(Val::Px(250.0), Val::Auto),
+                margin: UiRect::all(Val::Px(6.0)),
+                ..default()
+            },
+            ButtonSize::Small => Style {
+                margin: UiRect::all(Val::Px(4.0)),
+                padding: UiRect::new(Val::Px(10.0), Val::Px(10.0), Val::Px(2.0), Val::Px(2.0)),
+                ..default()
+            },
+        };
+        ButtonBundle {
+            style: Style {
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..style
+            },
+            color: self.colors.normal,
+            ..default()
+        }
+    }
+
+    pub fn button_text(&self, text: impl Into<String>, size: ButtonSize) -> TextBundle {
+        let section = match size {
+            ButtonSize::Large => self.fonts.h1(text.into(), TEXT_COLOR),
+            ButtonSize::Small => self.fonts.sub_title(text.into(), TEXT_COLOR),
+        };
+        TextBundle {
+            text: Text {
+                sections: vec![section],
+                alignment: Default::default(),
+            },
+            ..default()
+        }
+    }
+
+    // the action is the component click systems look for, its key is the button's text
+    pub fn button<'cw, 'cs, 'a, B: Component + Copy + LocaleKey>(
+        &self,
+        parent: &'a mut ChildBuilder<'cw, 'cs, '_>,
+        action: B,
+        size: ButtonSize,
+    ) -> EntityCommands<'cw, 'cs, 'a> {
+        let mut button = parent.spawn_bundle(self.button_bundle(size));
+        button
+            .with_children(|parent| {
+                parent.spawn_bundle(self.translated(
+                    action.key(),
+                    self.button_text(self.strings.get(action.key()), size),
+                ));
+            })
+            .insert(action);
+        button
+    }
+}